@@ -0,0 +1,196 @@
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::i2c::I2c;
+
+use crate::timed::ramp_code;
+use crate::{
+    Command, Error, Gain, Interrupted, OperatingMode, OverflowPolicy, RESET_CONTROL_WORD,
+    Resolution, encode_control, frame, is_nak,
+};
+
+/// Async version of [`AdafruitAD569x`](crate::AdafruitAD569x).
+pub struct AdafruitAD569xAsync<I2C> {
+    i2c: I2C,
+    addr: u8,
+    resolution: Resolution,
+    overflow_policy: OverflowPolicy,
+    input_code: Option<u16>,
+    dac_code: Option<u16>,
+}
+
+impl<I2C: I2c> AdafruitAD569xAsync<I2C> {
+    /// Create a driver for a 16-bit part.
+    pub fn new(i2c: I2C, addr: u8) -> Self {
+        Self::with_resolution(i2c, addr, Resolution::Bits16)
+    }
+
+    /// Create a driver for a part with the given resolution.
+    pub fn with_resolution(i2c: I2C, addr: u8, resolution: Resolution) -> Self {
+        debug_assert!(addr <= 0x7F, "I2C address must be 7-bit");
+
+        Self {
+            i2c,
+            addr,
+            resolution,
+            overflow_policy: OverflowPolicy::default(),
+            input_code: None,
+            dac_code: None,
+        }
+    }
+
+    /// Initialize the AD569x chip for communication.
+    ///
+    /// Will perform a soft reset and configure for normal mode,
+    /// with Vref on, and 1x gain output.
+    pub async fn begin(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.reset().await?;
+        self.set_mode(OperatingMode::NormalMode, true, Gain::X1)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Largest code accepted by the DAC for the configured resolution.
+    pub fn max_code(&self) -> u16 {
+        self.resolution.max_code()
+    }
+
+    /// Set how out-of-range codes are handled by the writes.
+    ///
+    /// Defaults to `OverflowPolicy::Error`, as on the blocking driver.
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// Write a 16-bit value to the input register... does NOT output it!
+    ///
+    /// The data does not appear on the output of the DAC till you run `update_dac()`!
+    ///
+    /// Values above `max_code()` are handled by the overflow policy.
+    pub async fn write_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        let code = self.limit_code(value as i64)?;
+        self.write(Command::WriteInput, self.resolution.justify(code))
+            .await?;
+        self.input_code = Some(code);
+
+        Ok(())
+    }
+
+    /// Update the DAC register from the input register.
+    pub async fn update_dac(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write(Command::UpdateDAC, 0x00).await?;
+        self.dac_code = self.input_code;
+
+        Ok(())
+    }
+
+    /// Write a 16-bit value to the input register and update the DAC
+    /// register.
+    ///
+    /// Values above `max_code()` are handled by the overflow policy.
+    pub async fn write_update_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        let code = self.limit_code(value as i64)?;
+        self.write(Command::WriteDACAndInput, self.resolution.justify(code))
+            .await?;
+        self.input_code = Some(code);
+        self.dac_code = self.input_code;
+
+        Ok(())
+    }
+
+    /// Ramp the output linearly from the current code to `target`.
+    ///
+    /// The async version of
+    /// [`AdafruitAD569x::ramp_to()`](crate::AdafruitAD569x::ramp_to). A `target`
+    /// above `max_code()` is handled by the overflow policy before anything is
+    /// written.
+    pub async fn ramp_to<D: DelayNs>(
+        &mut self,
+        target: u16,
+        steps: u16,
+        step_delay_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        let start = self.dac_code.ok_or(Error::UnknownState)?;
+        let target = self.limit_code(target as i64)?;
+        let steps = steps.max(1);
+
+        for step in 1..=steps {
+            self.write_update_dac(ramp_code(start, target, step, steps))
+                .await?;
+
+            if step != steps {
+                delay.delay_us(step_delay_us).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Play a buffer of codes out of the DAC at a fixed sample period.
+    ///
+    /// The async version of
+    /// [`AdafruitAD569x::play_samples()`](crate::AdafruitAD569x::play_samples).
+    /// Samples above `max_code()` are handled by the overflow policy as they
+    /// are played.
+    pub async fn play_samples<D: DelayNs>(
+        &mut self,
+        samples: &[u16],
+        sample_period_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        for (completed, &sample) in samples.iter().enumerate() {
+            self.write_update_dac(sample)
+                .await
+                .map_err(|error| Interrupted { completed, error })?;
+            delay.delay_us(sample_period_us).await;
+        }
+
+        Ok(())
+    }
+
+    /// Soft-reset the AD569x chip.
+    ///
+    /// Resets the DAC to zero-scale and resets the input, DAC, and control
+    /// registers to their default values. The NAK the chip gives as it resets
+    /// is treated as success.
+    pub async fn reset(&mut self) -> Result<(), Error<I2C::Error>> {
+        match self.write(Command::WriteControl, RESET_CONTROL_WORD).await {
+            Err(Error::I2c(error)) if is_nak(&error) => {}
+            result => result?,
+        }
+        self.input_code = Some(0);
+        self.dac_code = Some(0);
+
+        Ok(())
+    }
+
+    /// Set the operating mode, reference, and gain for the AD569x chip.
+    pub async fn set_mode(
+        &mut self,
+        mode: OperatingMode,
+        enable_ref: bool,
+        gain: Gain,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.write(
+            Command::WriteControl,
+            encode_control(mode, enable_ref, gain),
+        )
+        .await
+    }
+}
+
+impl<I2C: I2c> AdafruitAD569xAsync<I2C> {
+    /// Apply the overflow policy to a code that may be outside `0..=max_code()`.
+    fn limit_code(&self, code: i64) -> Result<u16, Error<I2C::Error>> {
+        self.overflow_policy
+            .limit(code, self.max_code())
+            .ok_or(Error::ValueOutOfRange)
+    }
+
+    async fn write(&mut self, command: Command, data: u16) -> Result<(), Error<I2C::Error>> {
+        let frame = frame(command, data);
+        trace!("write command {=u8:#04x} data {=u16:#06x}", frame[0], data);
+
+        self.i2c.write(self.addr, &frame).await.map_err(Error::I2c)
+    }
+}
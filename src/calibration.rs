@@ -0,0 +1,423 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, Microvolts, ShutdownControl};
+#[cfg(feature = "float")]
+use crate::{Volts, round_nearest};
+
+/// Gain and offset correction applied to codes from the voltage conversions
+///
+/// A nominal code is corrected to
+/// `code * gain_numerator / gain_denominator + code_offset`, rounded to the
+/// nearest code. A corrected code outside the code range is an error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Calibration {
+    /// Codes added after the gain correction.
+    pub code_offset: i16,
+    /// Numerator of the gain correction.
+    pub gain_numerator: u32,
+    /// Denominator of the gain correction, never zero.
+    pub gain_denominator: u32,
+}
+
+impl Default for Calibration {
+    /// No correction.
+    fn default() -> Self {
+        Self {
+            code_offset: 0,
+            gain_numerator: 1,
+            gain_denominator: 1,
+        }
+    }
+}
+
+impl Calibration {
+    /// Correct a nominal code, or `None` if the result is outside `0..=max_code`.
+    pub(crate) fn apply(&self, code: u16, max_code: u16) -> Option<u16> {
+        let denominator = self.gain_denominator as i64;
+        let scaled = (code as i64 * self.gain_numerator as i64 + denominator / 2) / denominator;
+        let corrected = scaled + self.code_offset as i64;
+
+        (0..=max_code as i64)
+            .contains(&corrected)
+            .then_some(corrected as u16)
+    }
+}
+
+/// Gain and offset of an external analog stage after the DAC output
+///
+/// The stage's output is
+/// `dac_mv * scale_numerator / scale_denominator + offset_mv`. A negative
+/// numerator describes an inverting stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutputStage {
+    /// Stage output, in millivolts, with the DAC at 0V.
+    pub offset_mv: i32,
+    /// Numerator of the stage gain, never zero.
+    pub scale_numerator: i32,
+    /// Denominator of the stage gain, never zero.
+    pub scale_denominator: u32,
+}
+
+impl Default for OutputStage {
+    /// The DAC output used directly.
+    fn default() -> Self {
+        Self {
+            offset_mv: 0,
+            scale_numerator: 1,
+            scale_denominator: 1,
+        }
+    }
+}
+
+/// A point in a temperature drift correction table
+///
+/// At `temperature_c`, codes are multiplied by `1 + gain_ppm / 1_000_000`, then
+/// `offset_code` is added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DriftPoint {
+    /// Temperature the correction was characterized at, in degrees Celsius.
+    pub temperature_c: i16,
+    /// Gain correction, in parts per million.
+    pub gain_ppm: i32,
+    /// Codes added after the gain correction.
+    pub offset_code: i16,
+}
+
+/// Linearly interpolate the drift correction at `temp_c`, as
+/// `(gain_ppm, offset_code)`.
+///
+/// `table` is sorted by temperature. Temperatures outside the table use the
+/// nearest end point, and an empty table gives no correction.
+#[cfg(feature = "float")]
+fn interpolate_drift(table: &[DriftPoint], temp_c: i16) -> (i32, i32) {
+    let (Some(first), Some(last)) = (table.first(), table.last()) else {
+        return (0, 0);
+    };
+    if temp_c <= first.temperature_c {
+        return (first.gain_ppm, first.offset_code as i32);
+    }
+    if temp_c >= last.temperature_c {
+        return (last.gain_ppm, last.offset_code as i32);
+    }
+
+    // The first point above temp_c exists, and has one below it
+    let above = table
+        .iter()
+        .position(|point| point.temperature_c > temp_c)
+        .unwrap_or(table.len() - 1);
+    let (low, high) = (table[above - 1], table[above]);
+
+    let run = high.temperature_c as i64 - low.temperature_c as i64;
+    let offset = temp_c as i64 - low.temperature_c as i64;
+    let lerp =
+        |from: i64, to: i64| round_div(((to - from) * offset) as i128, run as i128) as i64 + from;
+
+    (
+        lerp(low.gain_ppm as i64, high.gain_ppm as i64) as i32,
+        lerp(low.offset_code as i64, high.offset_code as i64) as i32,
+    )
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Set the temperature drift correction table used by
+    /// `write_voltage_at_temp()`.
+    ///
+    /// The points must be sorted by rising temperature.
+    pub fn set_drift_table(&mut self, table: &'static [DriftPoint]) {
+        debug_assert!(
+            table
+                .windows(2)
+                .all(|pair| pair[0].temperature_c < pair[1].temperature_c),
+            "drift table must be sorted by temperature"
+        );
+
+        self.drift_table = table;
+    }
+
+    /// Write a voltage corrected for drift at the given temperature.
+    ///
+    /// The gain and offset correction at `temp_c` is interpolated linearly from
+    /// the table set by `set_drift_table()`, holding the end points outside it.
+    /// The corrected code is then written like `write_voltage()`, including the
+    /// overflow policy and calibration.
+    #[cfg(feature = "float")]
+    pub fn write_voltage_at_temp(
+        &mut self,
+        volts: Volts,
+        temp_c: i16,
+    ) -> Result<(), Error<I2C::Error>> {
+        if self.config.vref_millivolts == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let (gain_ppm, offset_code) = interpolate_drift(self.drift_table, temp_c);
+        let exact = volts.0 / self.lsb_voltage() * (1.0 + gain_ppm as f32 / 1_000_000.0);
+        let code = round_nearest(exact + offset_code as f32);
+        if code.is_nan() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        // Float to int casts saturate, leaving the overflow policy to handle it
+        let code = self.limit_code(code as i64)?;
+
+        self.write_calibrated(code)
+    }
+
+    /// Describe the analog stage after the DAC used by `write_output_millivolts()`.
+    ///
+    /// The stage outputs `dac_mv * scale_numerator / scale_denominator + offset_mv`.
+    /// For example, a stage taking 0V to 2.5V to -5V to +5V is
+    /// `with_output_stage(-5000, 4, 1)`.
+    pub fn with_output_stage(
+        mut self,
+        offset_mv: i32,
+        scale_numerator: i32,
+        scale_denominator: u32,
+    ) -> Self {
+        self.output_stage = OutputStage {
+            offset_mv,
+            scale_numerator,
+            scale_denominator,
+        };
+        self
+    }
+
+    /// Describe a transconductance stage after the DAC, used by `write_microamps()`.
+    ///
+    /// The stage outputs `transconductance_microamps_per_volt` microamps for each
+    /// volt from the DAC, and may be negative for an inverting stage.
+    pub fn with_transconductance(mut self, transconductance_microamps_per_volt: i32) -> Self {
+        self.transconductance_microamps_per_volt = transconductance_microamps_per_volt;
+        self
+    }
+
+    /// Write a current at the output of the transconductance stage, in microamps.
+    ///
+    /// The target is mapped back through the stage set by `with_transconductance()`
+    /// to a DAC voltage, rounded to the nearest microvolt, then written like
+    /// `write_microvolts()`. Currents the stage can't reach within the code range
+    /// are handled by the overflow policy, returning `Error::ValueOutOfRange` by
+    /// default.
+    ///
+    /// Returns `Error::InvalidConfig` if no transconductance is set.
+    pub fn write_microamps(&mut self, ua: i32) -> Result<(), Error<I2C::Error>> {
+        let transconductance = self.transconductance_microamps_per_volt as i128;
+        if transconductance == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        // uv = ua / transconductance * 1_000_000
+        let numerator = ua as i128 * 1_000_000;
+        let (numerator, denominator) = if transconductance < 0 {
+            (-numerator, -transconductance)
+        } else {
+            (numerator, transconductance)
+        };
+        let uv = round_div(numerator, denominator);
+        if uv < 0 {
+            let code = self.limit_code(-1)?;
+            return self.write_calibrated(code);
+        }
+
+        self.write_microvolts(Microvolts(uv as u64))
+    }
+
+    /// Write a voltage at the output of the analog stage, in millivolts.
+    ///
+    /// The target is mapped back through the stage set by `with_output_stage()`
+    /// to a DAC code, rounded to the nearest code, and corrected by the
+    /// calibration like `write_millivolts()`. Targets the stage can't reach
+    /// within the code range are handled by the overflow policy, returning
+    /// `Error::ValueOutOfRange` by default.
+    ///
+    /// Returns `Error::InvalidConfig` if the stage has a zero scale or the
+    /// reference voltage is zero.
+    pub fn write_output_millivolts(&mut self, mv: i32) -> Result<(), Error<I2C::Error>> {
+        let stage = self.output_stage;
+        let full_scale = self.full_scale_millivolts() as i128;
+        if stage.scale_numerator == 0 || stage.scale_denominator == 0 || full_scale == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        // code = (mv - offset) / scale * span / full_scale
+        let numerator = (mv as i128 - stage.offset_mv as i128)
+            * stage.scale_denominator as i128
+            * self.code_span() as i128;
+        let denominator = stage.scale_numerator as i128 * full_scale;
+        let (numerator, denominator) = if denominator < 0 {
+            (-numerator, -denominator)
+        } else {
+            (numerator, denominator)
+        };
+        let code = round_div(numerator, denominator);
+
+        let code = self.limit_code(code.clamp(i64::MIN as i128, i64::MAX as i128) as i64)?;
+        self.write_calibrated(code)
+    }
+
+    /// Set the gain and offset correction applied by the voltage writes.
+    ///
+    /// `write_voltage()` and `write_millivolts()` correct their code to
+    /// `code * gain_numerator / gain_denominator + code_offset` before writing it,
+    /// while `write_dac()` and the other code writes are left uncorrected. Returns
+    /// `Error::InvalidConfig` if `gain_denominator` is zero.
+    pub fn set_calibration(
+        &mut self,
+        code_offset: i16,
+        gain_numerator: u32,
+        gain_denominator: u32,
+    ) -> Result<(), Error<I2C::Error>> {
+        if gain_denominator == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        self.calibration = Calibration {
+            code_offset,
+            gain_numerator,
+            gain_denominator,
+        };
+
+        Ok(())
+    }
+
+    /// Fit the calibration to voltages measured at the DAC output.
+    ///
+    /// Each of the `targets`, in millivolts, is converted to its nominal code and
+    /// written uncorrected with `write_update_dac()`, then `measure` is called to
+    /// read the output in microvolts. `measure` should wait for the output to
+    /// settle before reading it. A least-squares line through the measurements
+    /// gives the gain and offset correction, which replaces the calibration and
+    /// is returned.
+    ///
+    /// Returns `Error::InvalidConfig` if there are fewer than two distinct target
+    /// codes, the measurements don't rise with the code, or the reference voltage
+    /// is zero, and `Error::ValueOutOfRange` if the offset doesn't fit an `i16` or
+    /// the gain numerator a `u32`. The calibration isn't changed on an error.
+    pub fn calibrate_against<F: FnMut() -> u32>(
+        &mut self,
+        targets: &[u32],
+        mut measure: F,
+    ) -> Result<Calibration, Error<I2C::Error>> {
+        let full_scale_uv = self.full_scale_millivolts() as i128 * 1000;
+        if full_scale_uv == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let span = self.code_span() as i128;
+
+        let (mut sx, mut sy, mut sxx, mut sxy) = (0i128, 0i128, 0i128, 0i128);
+        for &target in targets {
+            let code = (target as i128 * 1000 * span + full_scale_uv / 2) / full_scale_uv;
+            let code = self.limit_code(code.min(i64::MAX as i128) as i64)?;
+            self.write_update_dac(code)?;
+
+            let (x, y) = (code as i128, measure() as i128);
+            sx += x;
+            sy += y;
+            sxx += x * x;
+            sxy += x * y;
+        }
+
+        self.calibration = self.fit_calibration(targets.len() as i128, sx, sy, sxx, sxy)?;
+
+        Ok(self.calibration)
+    }
+
+    /// Set the calibration from the output measured at two codes.
+    ///
+    /// `low` and `high` are `(code, measured_microvolts)` pairs. The straight line
+    /// through them gives the gain and offset correction, so that the voltage
+    /// writes land on the voltages asked for. The points can be taken with
+    /// `write_update_dac()` and a meter, ideally near the ends of the range.
+    ///
+    /// Returns `Error::InvalidConfig` if the codes are equal, the measurements
+    /// don't rise with the code, or the reference voltage is zero, and
+    /// `Error::ValueOutOfRange` if the correction doesn't fit a `Calibration`, as
+    /// for `calibrate_against()`. The calibration isn't changed on an error.
+    pub fn set_two_point_calibration(
+        &mut self,
+        low: (u16, u32),
+        high: (u16, u32),
+    ) -> Result<(), Error<I2C::Error>> {
+        let (x0, y0) = (low.0 as i128, low.1 as i128);
+        let (x1, y1) = (high.0 as i128, high.1 as i128);
+
+        self.calibration =
+            self.fit_calibration(2, x0 + x1, y0 + y1, x0 * x0 + x1 * x1, x0 * y0 + x1 * y1)?;
+
+        Ok(())
+    }
+
+    /// The gain and offset correction applied by the voltage writes.
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    /// Remove the calibration, so the voltage writes use their nominal codes.
+    pub fn clear_calibration(&mut self) {
+        self.calibration = Calibration::default();
+    }
+
+    /// Gain and offset correction for a least-squares line through `n` points,
+    /// given the sums of the codes, measured microvolts, squared codes and
+    /// products of the two.
+    fn fit_calibration(
+        &self,
+        n: i128,
+        sx: i128,
+        sy: i128,
+        sxx: i128,
+        sxy: i128,
+    ) -> Result<Calibration, Error<I2C::Error>> {
+        let full_scale_uv = self.full_scale_millivolts() as i128 * 1000;
+        if full_scale_uv == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        let span = self.code_span() as i128;
+
+        // Measured microvolts are slope * code + intercept, with
+        // slope = rise / run and intercept = (sy * run - rise * sx) / (n * run)
+        let run = n * sxx - sx * sx;
+        let rise = n * sxy - sx * sy;
+        if run <= 0 || rise <= 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        // The corrected code for a nominal code is
+        // (nominal * lsb - intercept) / slope, with lsb = full_scale_uv / span
+        let gain_denominator = 1u32 << 16;
+        let gain_numerator = round_div(full_scale_uv * run * gain_denominator as i128, span * rise);
+        let code_offset = round_div(-(sy * run - rise * sx), n * rise);
+
+        Ok(Calibration {
+            code_offset: i16::try_from(code_offset).map_err(|_| Error::ValueOutOfRange)?,
+            gain_numerator: u32::try_from(gain_numerator).map_err(|_| Error::ValueOutOfRange)?,
+            gain_denominator,
+        })
+    }
+
+    /// Write and update a nominal code from the voltage conversions, corrected
+    /// by the calibration.
+    ///
+    /// Returns `Error::ValueOutOfRange` if the correction moves the code outside
+    /// the code range, whatever the overflow policy, as the output couldn't reach
+    /// the voltage asked for.
+    pub(crate) fn write_calibrated(&mut self, code: u16) -> Result<(), Error<I2C::Error>> {
+        let corrected = self
+            .calibration
+            .apply(code, self.max_code())
+            .ok_or(Error::ValueOutOfRange)?;
+
+        self.write_update_dac(corrected)
+    }
+}
+
+/// Divide, rounding to the nearest integer with halfway values rounded up.
+///
+/// `denominator` must be positive.
+fn round_div(numerator: i128, denominator: i128) -> i128 {
+    (2 * numerator + denominator).div_euclid(2 * denominator)
+}
@@ -0,0 +1,124 @@
+use crate::{Calibration, Gain, INTERNAL_VREF_MV, Model, OperatingMode};
+
+/// AD569x configuration applied by `begin()`.
+///
+/// The default matches the chip's power-on state: normal mode, with the 2.5V
+/// internal reference on, and 1x gain output.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    pub(crate) vref_millivolts: u32,
+    pub(crate) gain: Gain,
+    pub(crate) reference_enabled: bool,
+    pub(crate) mode: OperatingMode,
+    pub(crate) power_on_output_code: Option<u16>,
+    pub(crate) calibration: Calibration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            vref_millivolts: INTERNAL_VREF_MV,
+            gain: Gain::X1,
+            reference_enabled: true,
+            mode: OperatingMode::NormalMode,
+            power_on_output_code: None,
+            calibration: Calibration::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Set the reference voltage, in millivolts, used by the voltage conversions.
+    pub fn with_vref(mut self, vref_millivolts: u32) -> Self {
+        self.vref_millivolts = vref_millivolts;
+        self
+    }
+
+    /// Set the output gain.
+    pub fn with_gain(mut self, gain: Gain) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Enable or disable the internal reference.
+    pub fn with_reference(mut self, enabled: bool) -> Self {
+        self.reference_enabled = enabled;
+        self
+    }
+
+    /// Set the operating mode.
+    pub fn with_mode(mut self, mode: OperatingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set a code for `begin()` to output once the chip is configured, instead of
+    /// leaving it at zero-scale.
+    pub fn with_power_on_output_code(mut self, code: u16) -> Self {
+        self.power_on_output_code = Some(code);
+        self
+    }
+
+    /// Set stored gain and offset correction for the voltage writes, as from
+    /// `calibrate_against()`.
+    ///
+    /// The driver takes it when it is created with the config, or given it by
+    /// `begin_with_output()`, like `set_calibration()`. A zero `gain_den` makes
+    /// the config invalid.
+    pub fn with_calibration(mut self, offset_code: i16, gain_num: u32, gain_den: u32) -> Self {
+        self.calibration = Calibration {
+            code_offset: offset_code,
+            gain_numerator: gain_num,
+            gain_denominator: gain_den,
+        };
+        self
+    }
+
+    /// Check the settings can be used with `model`, without a driver.
+    ///
+    /// Returns the first problem found, in the order of the `ConfigError`
+    /// variants. The driver checks the same rules as it uses the config, so this
+    /// only moves the error earlier.
+    pub fn validate_for(&self, model: Model) -> Result<(), ConfigError> {
+        if self.reference_enabled && !model.has_internal_reference() {
+            return Err(ConfigError::ReferenceNotAvailable);
+        }
+        if self.vref_millivolts == 0 {
+            return Err(ConfigError::ZeroReferenceVoltage);
+        }
+        let max_code = model.resolution().max_code();
+        if let Some(code) = self.power_on_output_code.filter(|&code| code > max_code) {
+            return Err(ConfigError::PowerOnCodeOutOfRange { code, max_code });
+        }
+        if self.calibration.gain_denominator == 0 {
+            return Err(ConfigError::ZeroGainDenominator);
+        }
+
+        Ok(())
+    }
+
+    /// Check the settings can be used together.
+    pub(crate) fn is_valid(&self) -> bool {
+        self.vref_millivolts != 0 && self.calibration.gain_denominator != 0
+    }
+}
+
+/// A problem found by `Config::validate_for()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// The internal reference is enabled on a model without one, the AD5693.
+    ReferenceNotAvailable,
+    /// The reference voltage is zero, so the voltage conversions can't be used.
+    ZeroReferenceVoltage,
+    /// The power-on output code is above the model's largest code.
+    PowerOnCodeOutOfRange {
+        /// The power-on output code.
+        code: u16,
+        /// The largest code the model accepts.
+        max_code: u16,
+    },
+    /// The calibration's `gain_denominator` is zero.
+    ZeroGainDenominator,
+}
@@ -0,0 +1,73 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    AdafruitAD569x, ControlChange, ControlRegister, Error, Gain, OperatingMode, ShutdownControl,
+};
+
+/// Control register settings being gathered by `configure()`
+///
+/// Each setter replaces one field, and `apply()` writes them all in a single
+/// control register write.
+#[must_use = "nothing is written until `apply()` is called"]
+pub struct ConfigInProgress<'a, I2C, LDAC, RST, SHDN> {
+    dac: &'a mut AdafruitAD569x<I2C, LDAC, RST, SHDN>,
+    control: ControlRegister,
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Start changing the control register one setting at a time.
+    ///
+    /// The settings start from those last written by the driver, or the power-on
+    /// defaults if it hasn't configured the chip yet, so only the ones that
+    /// change need to be set:
+    ///
+    /// ```
+    /// use ad569x::{AdafruitAD569x, Gain, Model};
+    ///
+    /// let mut dac = AdafruitAD569x::shadow(Model::AD5693R);
+    /// dac.begin().unwrap();
+    /// dac.configure().reference(false).gain(Gain::X2).apply().unwrap();
+    ///
+    /// assert_eq!(dac.last_control_word(), Some(0x1800));
+    /// ```
+    pub fn configure(&mut self) -> ConfigInProgress<'_, I2C, LDAC, RST, SHDN> {
+        self.sync_if_lazy();
+        let control = self.control_or_default();
+
+        ConfigInProgress { dac: self, control }
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> ConfigInProgress<'_, I2C, LDAC, RST, SHDN> {
+    /// Set the operating mode.
+    pub fn mode(mut self, mode: OperatingMode) -> Self {
+        self.control.mode = mode;
+        self
+    }
+
+    /// Set whether the internal reference is enabled.
+    pub fn reference(mut self, enabled: bool) -> Self {
+        self.control.reference_enabled = enabled;
+        self
+    }
+
+    /// Set the output gain.
+    pub fn gain(mut self, gain: Gain) -> Self {
+        self.control.gain = gain;
+        self
+    }
+
+    /// Write the gathered settings in one control register write.
+    ///
+    /// Behaves like `set_mode()` with the same settings: an unchanged register
+    /// isn't rewritten, and the state it replaced is returned.
+    pub fn apply(self) -> Result<ControlChange, Error<I2C::Error>> {
+        let ControlRegister {
+            mode,
+            reference_enabled,
+            gain,
+        } = self.control;
+
+        self.dac.set_mode(mode, reference_enabled, gain)
+    }
+}
@@ -0,0 +1,31 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, Millivolts, ShutdownControl};
+
+/// A single-channel DAC, for code that is generic over DAC drivers
+pub trait SingleChannelDac {
+    /// Error returned by the DAC operations.
+    type Error;
+
+    /// Set the output to a raw code.
+    fn set_code(&mut self, code: u16) -> Result<(), Self::Error>;
+
+    /// Set the output to a voltage in millivolts.
+    fn set_voltage(&mut self, millivolts: Millivolts) -> Result<(), Self::Error>;
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> SingleChannelDac
+    for AdafruitAD569x<I2C, LDAC, RST, SHDN>
+{
+    type Error = Error<I2C::Error>;
+
+    /// Same as `write_update_dac()`.
+    fn set_code(&mut self, code: u16) -> Result<(), Self::Error> {
+        self.write_update_dac(code)
+    }
+
+    /// Same as `write_millivolts()`.
+    fn set_voltage(&mut self, millivolts: Millivolts) -> Result<(), Self::Error> {
+        self.write_millivolts(millivolts)
+    }
+}
@@ -0,0 +1,132 @@
+use embedded_hal::digital;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+
+/// AD569x driver errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// An error from the I2C bus.
+    I2c(E),
+    /// An error from a GPIO pin, as its kind.
+    ///
+    /// The driver's pins can each have a different error type, so the error
+    /// itself isn't kept.
+    Pin(digital::ErrorKind),
+    /// A code or voltage was outside the range the DAC can output.
+    ValueOutOfRange,
+    /// The driver configuration can't be used for the operation.
+    InvalidConfig,
+    /// The internal reference was enabled on a model without one, the AD5693.
+    ReferenceNotAvailable,
+    /// The operation needs chip state the driver doesn't know, such as the
+    /// current code before anything has been written.
+    UnknownState,
+    /// A register read back didn't hold the value written to it.
+    VerifyMismatch {
+        /// The value written.
+        expected: u16,
+        /// The value read back.
+        actual: u16,
+    },
+    /// `write_update_dac()` failed on the bus, so the chip may or may not have
+    /// taken the code.
+    WriteInterrupted {
+        /// The bus error from the write.
+        error: E,
+        /// The code read back from the input register after the error, or `None`
+        /// if it couldn't be read.
+        readback: Option<u16>,
+    },
+    /// A write would have moved the output further than the limit set by
+    /// `set_max_slew()`.
+    SlewLimitExceeded {
+        /// The code being output.
+        previous: u16,
+        /// The code that was refused.
+        requested: u16,
+    },
+    /// A register didn't take a test pattern written by `verify_writable()`.
+    StuckRegister {
+        /// The pattern written.
+        expected: u16,
+        /// The value read back.
+        actual: u16,
+    },
+    /// A write would have moved the output against the direction set by
+    /// `begin_monotonic()`.
+    MonotonicityViolation {
+        /// The code being output.
+        previous: u16,
+        /// The code that was refused.
+        requested: u16,
+    },
+    /// The output was changed while the chip is powered down, without
+    /// `allow_write_while_powered_down()`.
+    NotInOutputMode,
+    /// The output was changed while the external output stage is shut down, under
+    /// `DisabledOutputPolicy::Refuse`.
+    OutputDisabled,
+    /// A preset index wasn't below `PRESET_COUNT`.
+    InvalidPreset,
+    /// A preset was recalled that hasn't been set with `set_preset()`.
+    PresetNotSet,
+    /// A write came sooner after the last one than the `RateLimit` allows,
+    /// under `RateLimitPolicy::Refuse` with no delay.
+    RateLimited {
+        /// Time until the write would be allowed, in microseconds.
+        wait_us: u32,
+    },
+}
+
+impl<E: embedded_hal::i2c::Error> Error<E> {
+    /// The kind of the I2C bus error, or `None` if this isn't a bus error.
+    ///
+    /// This includes the bus error in `Error::WriteInterrupted`.
+    ///
+    /// Lets callers tell a missing device (`ErrorKind::NoAcknowledge`) from bus
+    /// contention (`ErrorKind::ArbitrationLoss`) without knowing the HAL's error
+    /// type.
+    pub fn i2c_kind(&self) -> Option<ErrorKind> {
+        match self {
+            Error::I2c(error) | Error::WriteInterrupted { error, .. } => Some(error.kind()),
+            _ => None,
+        }
+    }
+
+    /// Whether the error is a bus error from no device answering at the address.
+    ///
+    /// True for `ErrorKind::NoAcknowledge` of the address, or of an unknown
+    /// part of the transaction. A NAK of the data means a device answered and
+    /// doesn't count.
+    pub fn is_device_missing(&self) -> bool {
+        matches!(
+            self.i2c_kind(),
+            Some(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address | NoAcknowledgeSource::Unknown
+            ))
+        )
+    }
+
+    /// Whether the error is a bus error from the bus being busy or too slow.
+    ///
+    /// True for `ErrorKind::ArbitrationLoss`, and for `ErrorKind::Other`, which
+    /// is how HALs without a dedicated kind report timeouts such as a device
+    /// stretching the clock for too long. These are worth retrying after a back
+    /// off, unlike a missing device.
+    pub fn is_bus_busy(&self) -> bool {
+        matches!(
+            self.i2c_kind(),
+            Some(ErrorKind::ArbitrationLoss | ErrorKind::Other)
+        )
+    }
+}
+
+/// An error partway through a sequence of writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Interrupted<E> {
+    /// Number of writes that completed before the error.
+    pub completed: usize,
+    /// The error that stopped the sequence.
+    pub error: Error<E>,
+}
@@ -0,0 +1,159 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{Command, Error, Interrupted, Resolution, frame};
+
+/// Several AD569x chips of the same resolution sharing one I2C bus.
+///
+/// Writes go to each address in order, one transaction per chip.
+pub struct Group<I2C, const N: usize> {
+    i2c: I2C,
+    addresses: [u8; N],
+    resolution: Resolution,
+}
+
+impl<I2C: I2c, const N: usize> Group<I2C, N> {
+    /// Create a group of 16-bit parts at the given addresses.
+    pub fn new(i2c: I2C, addresses: [u8; N]) -> Self {
+        Self::with_resolution(i2c, addresses, Resolution::Bits16)
+    }
+
+    /// Create a group of parts with the given resolution.
+    pub fn with_resolution(i2c: I2C, addresses: [u8; N], resolution: Resolution) -> Self {
+        debug_assert!(
+            addresses.iter().all(|&addr| addr <= 0x7F),
+            "I2C addresses must be 7-bit"
+        );
+
+        Self {
+            i2c,
+            addresses,
+            resolution,
+        }
+    }
+
+    /// Addresses of the chips in the group, in write order.
+    pub fn addresses(&self) -> &[u8; N] {
+        &self.addresses
+    }
+
+    /// Largest code accepted by the DACs for the configured resolution.
+    pub fn max_code(&self) -> u16 {
+        self.resolution.max_code()
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Write the same value to the input and DAC registers of every chip.
+    ///
+    /// Stops at the first chip that fails. The error's `completed` count is the
+    /// index of that chip in `addresses()`: the chips before it took the write
+    /// and the chips after it were not written.
+    ///
+    /// Returns `Error::ValueOutOfRange` with nothing written if `value` is
+    /// above `max_code()`.
+    pub fn write_all(&mut self, value: u16) -> Result<(), Interrupted<I2C::Error>> {
+        if value > self.max_code() {
+            return Err(Interrupted {
+                completed: 0,
+                error: Error::ValueOutOfRange,
+            });
+        }
+
+        let frame = frame(Command::WriteDACAndInput, self.resolution.justify(value));
+        for (completed, &addr) in self.addresses.iter().enumerate() {
+            trace!("write {=u8:#04x} command {=u8:#04x}", addr, frame[0]);
+            self.i2c.write(addr, &frame).map_err(|error| Interrupted {
+                completed,
+                error: Error::I2c(error),
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Two AD569x chips of the same resolution driving a differential signal.
+///
+/// The positive side gets a code and the negative side its complement,
+/// `max_code() - code`, one transaction per chip.
+pub struct DifferentialPair<I2C> {
+    i2c: I2C,
+    positive: u8,
+    negative: u8,
+    resolution: Resolution,
+}
+
+impl<I2C: I2c> DifferentialPair<I2C> {
+    /// Create a pair of 16-bit parts at the given addresses.
+    pub fn new(i2c: I2C, positive: u8, negative: u8) -> Self {
+        Self::with_resolution(i2c, positive, negative, Resolution::Bits16)
+    }
+
+    /// Create a pair of parts with the given resolution.
+    pub fn with_resolution(i2c: I2C, positive: u8, negative: u8, resolution: Resolution) -> Self {
+        debug_assert!(
+            positive <= 0x7F && negative <= 0x7F,
+            "I2C addresses must be 7-bit"
+        );
+
+        Self {
+            i2c,
+            positive,
+            negative,
+            resolution,
+        }
+    }
+
+    /// Addresses of the positive and negative sides, in write order.
+    pub fn addresses(&self) -> (u8, u8) {
+        (self.positive, self.negative)
+    }
+
+    /// Largest code accepted by the DACs for the configured resolution.
+    pub fn max_code(&self) -> u16 {
+        self.resolution.max_code()
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Write `code` to the positive side and `max_code() - code` to the negative
+    /// side, each to the input and DAC registers.
+    ///
+    /// The positive side is written first. The error's `completed` count says
+    /// which write failed: zero if the positive side failed and the negative side
+    /// wasn't written, or one if the positive side took the new code and the
+    /// negative side didn't, leaving the pair mismatched until the next
+    /// successful write.
+    ///
+    /// Returns `Error::ValueOutOfRange` with nothing written if `code` is above
+    /// `max_code()`.
+    pub fn write_differential(&mut self, code: u16) -> Result<(), Interrupted<I2C::Error>> {
+        if code > self.max_code() {
+            return Err(Interrupted {
+                completed: 0,
+                error: Error::ValueOutOfRange,
+            });
+        }
+
+        let sides = [
+            (self.positive, code),
+            (self.negative, self.max_code() - code),
+        ];
+        for (completed, (addr, code)) in sides.into_iter().enumerate() {
+            let frame = frame(Command::WriteDACAndInput, self.resolution.justify(code));
+            trace!("write {=u8:#04x} command {=u8:#04x}", addr, frame[0]);
+            self.i2c.write(addr, &frame).map_err(|error| Interrupted {
+                completed,
+                error: Error::I2c(error),
+            })?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, ShutdownControl};
+
+/// Borrows a driver and tristates its output when dropped, from
+/// `guard_tristate()`
+///
+/// The guard derefs to the driver, so it can be used as one while it lives.
+pub struct TristateOnDrop<'a, I2C: I2c, LDAC, RST, SHDN: ShutdownControl> {
+    dac: &'a mut AdafruitAD569x<I2C, LDAC, RST, SHDN>,
+    on_error: Option<fn(Error<I2C::Error>)>,
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Borrow the driver in a guard that tristates the output when it goes out
+    /// of scope.
+    ///
+    /// Drop can't return errors, so a failed tristate write is passed to
+    /// `on_error` if one is given and otherwise ignored.
+    pub fn guard_tristate(
+        &mut self,
+        on_error: Option<fn(Error<I2C::Error>)>,
+    ) -> TristateOnDrop<'_, I2C, LDAC, RST, SHDN> {
+        TristateOnDrop {
+            dac: self,
+            on_error,
+        }
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> Deref
+    for TristateOnDrop<'_, I2C, LDAC, RST, SHDN>
+{
+    type Target = AdafruitAD569x<I2C, LDAC, RST, SHDN>;
+
+    fn deref(&self) -> &Self::Target {
+        self.dac
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> DerefMut
+    for TristateOnDrop<'_, I2C, LDAC, RST, SHDN>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.dac
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> Drop for TristateOnDrop<'_, I2C, LDAC, RST, SHDN> {
+    fn drop(&mut self) {
+        // Not held back by the rate limit, so the output is made safe at once
+        let rate_limit = self.dac.rate_limit.take();
+        let result = self.dac.into_tristate();
+        self.dac.rate_limit = rate_limit;
+
+        if let (Err(error), Some(on_error)) = (result, self.on_error) {
+            on_error(error);
+        }
+    }
+}
@@ -1,8 +1,115 @@
 #![no_std]
 
-use embedded_hal::i2c::I2c;
+use core::ops::RangeInclusive;
+
+use embedded_hal::i2c::{Error as _, ErrorKind, I2c, Operation};
+
+/// Log at trace level with defmt, compiling to nothing without the `defmt` feature.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::trace!($($arg)*);
+    };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "defmt")]
+        defmt::warn!($($arg)*);
+    };
+}
+
+#[cfg(feature = "async")]
+mod asynch;
+mod calibration;
+mod config;
+mod configure;
+mod dac;
+mod error;
+mod group;
+mod guard;
+mod pins;
+mod preset;
+mod rate;
+mod sequence;
+mod shadow;
+mod split;
+mod stats;
+mod timed;
+mod units;
+pub mod waveform;
+
+#[cfg(feature = "async")]
+pub use asynch::AdafruitAD569xAsync;
+pub use calibration::{Calibration, DriftPoint, OutputStage};
+pub use config::{Config, ConfigError};
+pub use configure::ConfigInProgress;
+pub use dac::SingleChannelDac;
+pub use error::{Error, Interrupted};
+pub use group::{DifferentialPair, Group};
+pub use guard::TristateOnDrop;
+pub use pins::{NoPin, ShutdownControl};
+pub use preset::PRESET_COUNT;
+pub use rate::{Clock, DelayUs, RateLimit, RateLimitPolicy};
+pub use sequence::{Prepared, Step};
+pub use shadow::ShadowBus;
+pub use split::{DacConfig, DacWriter};
+pub use stats::DriverStats;
+pub use timed::EasingCurve;
+#[cfg(feature = "float")]
+pub use units::Volts;
+pub use units::{Microvolts, Millivolts};
+
+#[cfg(any(
+    all(feature = "model-ad5691r", feature = "model-ad5692r"),
+    all(feature = "model-ad5691r", feature = "model-ad5693r"),
+    all(feature = "model-ad5691r", feature = "model-ad5693"),
+    all(feature = "model-ad5692r", feature = "model-ad5693r"),
+    all(feature = "model-ad5692r", feature = "model-ad5693"),
+    all(feature = "model-ad5693r", feature = "model-ad5693"),
+))]
+compile_error!("only one of the `model-*` features can be enabled");
+
+/// Model used by `AdafruitAD569x::default_for_feature()`, set by the `model-*`
+/// features and the AD5693R if none is enabled.
+pub const FEATURE_MODEL: Model = if cfg!(feature = "model-ad5691r") {
+    Model::AD5691R
+} else if cfg!(feature = "model-ad5692r") {
+    Model::AD5692R
+} else if cfg!(feature = "model-ad5693") {
+    Model::AD5693
+} else {
+    Model::AD5693R
+};
+
+/// Voltage of the internal reference on the AD569xR parts, in millivolts.
+pub const INTERNAL_VREF_MV: u32 = 2500;
+
+/// DAC code after a power-on or software reset, zero-scale.
+pub const POR_CODE: u16 = 0;
+
+/// Control word after a power-on or software reset.
+///
+/// Normal mode, reference enabled, and 1x gain. Models without an internal
+/// reference read back the same word.
+pub const POR_CONTROL_WORD: u16 = 0x0000;
+
+/// Control word written by `reset()`, with only the reset bit set.
+pub const RESET_CONTROL_WORD: u16 = 0x8000;
+
+/// Typical output settling time from the datasheet, in microseconds.
+///
+/// This is for a quarter to three-quarter scale step into the datasheet's test
+/// load. Larger capacitive loads settle more slowly.
+pub const SETTLE_TIME_US: u32 = 5;
+
+/// Time added to every step by `estimated_settle_us()`, in microseconds, for the
+/// digital delay and the final approach to within an LSB.
+pub const MIN_SETTLE_US: u32 = 2;
 
 /// AD569x commands
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Command {
     /// No operation command.
     NOP = 0x00,
@@ -16,7 +123,20 @@ pub enum Command {
     WriteControl = 0x40,
 }
 
+impl Command {
+    /// The command byte that starts each frame.
+    ///
+    /// This is a byte rather than a control word field, as commands are sent
+    /// ahead of the 16-bit data word.
+    pub const fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
 /// AD569x operating modes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OperatingMode {
     /// Normal operating mode.
     NormalMode = 0x00,
@@ -28,86 +148,2212 @@ pub enum OperatingMode {
     OutputTristate = 0x03,
 }
 
-pub struct AdafruitAD569x<I2C> {
+impl OperatingMode {
+    /// The mode's bits in place in the control word, bits 14 and 13.
+    pub const fn bits(self) -> u16 {
+        (self as u16) << 13
+    }
+}
+
+/// AD569x output gains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gain {
+    /// Output spans 0V to Vref.
+    X1 = 0x00,
+    /// Output spans 0V to 2 x Vref.
+    X2 = 0x01,
+}
+
+impl Gain {
+    /// The gain bit in place in the control word, bit 11.
+    pub const fn bits(self) -> u16 {
+        (self as u16) << 11
+    }
+}
+
+/// Decoded contents of the AD569x control register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlRegister {
+    /// Operating mode.
+    pub mode: OperatingMode,
+    /// Whether the internal reference is enabled.
+    pub reference_enabled: bool,
+    /// Output gain.
+    pub gain: Gain,
+}
+
+/// Result of a control register change by `set_mode()` and friends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlChange {
+    /// The control register state before the change.
+    pub previous: ControlRegister,
+    /// Whether the control register was written, or skipped as unchanged.
+    pub written: bool,
+}
+
+impl Default for ControlRegister {
+    /// The power-on state: normal mode, with the reference on, and 1x gain.
+    fn default() -> Self {
+        Self {
+            mode: OperatingMode::NormalMode,
+            reference_enabled: true,
+            gain: Gain::X1,
+        }
+    }
+}
+
+/// AD569x I2C addresses, set by how the A0 pin is strapped
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Address {
+    /// A0 tied low, the default on the Adafruit breakout.
+    #[default]
+    A0Low = 0x4C,
+    /// A0 tied high.
+    A0High = 0x4E,
+}
+
+impl From<Address> for u8 {
+    fn from(address: Address) -> Self {
+        address as u8
+    }
+}
+
+/// AD569x device models
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Model {
+    /// 12-bit with internal reference.
+    AD5691R,
+    /// 14-bit with internal reference.
+    AD5692R,
+    /// 16-bit with internal reference, as on the Adafruit breakout.
+    AD5693R,
+    /// 16-bit without internal reference.
+    AD5693,
+}
+
+impl Model {
+    /// Resolution of the DAC.
+    pub const fn resolution(self) -> Resolution {
+        match self {
+            Model::AD5691R => Resolution::Bits12,
+            Model::AD5692R => Resolution::Bits14,
+            Model::AD5693R | Model::AD5693 => Resolution::Bits16,
+        }
+    }
+
+    /// Whether the part has an internal reference.
+    pub const fn has_internal_reference(self) -> bool {
+        !matches!(self, Model::AD5693)
+    }
+
+    /// Settling time for a zero to full-scale step, in microseconds.
+    ///
+    /// Double the datasheet's typical `SETTLE_TIME_US` for a half-scale step, as
+    /// a large step settles mostly at the output amplifier's slew rate.
+    ///
+    /// This doesn't depend on the model. The datasheet gives one settling time
+    /// for the whole family, which shares the same output amplifier.
+    pub const fn full_scale_settle_us(self) -> u32 {
+        2 * SETTLE_TIME_US
+    }
+}
+
+/// Rounding used when converting a voltage to a code
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RoundMode {
+    /// Round to the nearest code, with ties rounded up.
+    Nearest,
+    /// Round down, never overshooting the voltage.
+    Floor,
+    /// Round up, never undershooting the voltage.
+    Ceil,
+}
+
+/// How out-of-range codes and voltages are handled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OverflowPolicy {
+    /// Clamp to the nearest end of the code range.
+    Clamp,
+    /// Return `Error::ValueOutOfRange`.
+    #[default]
+    Error,
+}
+
+impl OverflowPolicy {
+    /// Apply the policy to a code that may be outside `0..=max_code`, or `None`
+    /// under `OverflowPolicy::Error`.
+    pub(crate) fn limit(self, code: i64, max_code: u16) -> Option<u16> {
+        let max_code = max_code as i64;
+        if (0..=max_code).contains(&code) {
+            return Some(code as u16);
+        }
+
+        match self {
+            OverflowPolicy::Clamp => Some(code.clamp(0, max_code) as u16),
+            OverflowPolicy::Error => None,
+        }
+    }
+}
+
+/// What `write_update_dac()`, `update_dac()`, and `update_via_ldac()` do while
+/// `disable_output()` has shut down the external output stage
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DisabledOutputPolicy {
+    /// Write as usual, leaving the stage shut down.
+    #[default]
+    Allow,
+    /// Return `Error::OutputDisabled` without writing.
+    Refuse,
+    /// Enable the stage with `enable_output()` before writing.
+    AutoEnable,
+}
+
+/// Whether `update_dac()` or `update_via_ldac()` output a new code
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum UpdateKind {
+    /// A code written with `write_dac()` since the last update.
+    Fresh,
+    /// Nothing was written since the last update, so the input register was
+    /// probably stale. The update is made all the same.
+    Stale,
+}
+
+/// Direction the output is allowed to move in by `begin_monotonic()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// The code may stay the same or increase.
+    Rising,
+    /// The code may stay the same or decrease.
+    Falling,
+}
+
+/// AD569x DAC resolutions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Resolution {
+    /// 12-bit, as on the AD5691R.
+    Bits12 = 12,
+    /// 14-bit, as on the AD5692R.
+    Bits14 = 14,
+    /// 16-bit, as on the AD5693R and AD5693.
+    Bits16 = 16,
+}
+
+impl Resolution {
+    /// Number of bits in a DAC code.
+    pub const fn bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Largest code accepted by the DAC, `(1 << bits) - 1`.
+    pub const fn max_code(self) -> u16 {
+        ((1u32 << self.bits()) - 1) as u16
+    }
+
+    /// Number of bits a code is shifted left by to left-justify it in the 16-bit word.
+    const fn shift(self) -> u8 {
+        16 - self.bits()
+    }
+
+    /// Saturate a code to `max_code()` and left-justify it in the 16-bit word.
+    const fn justify(self, code: u16) -> u16 {
+        let max_code = self.max_code();
+        let code = if code > max_code { max_code } else { code };
+
+        code << self.shift()
+    }
+
+    /// Right-justify the code held in a 16-bit register word.
+    const fn unjustify(self, word: u16) -> u16 {
+        word >> self.shift()
+    }
+}
+
+/// Pack the operating mode, reference, and gain into a control register word.
+///
+/// This is the data word `set_mode()` writes to the control register.
+pub const fn encode_control(mode: OperatingMode, reference: bool, gain: Gain) -> u16 {
+    mode.bits() | ((!reference as u16) << 12) | gain.bits()
+}
+
+/// Unpack a control register word into the operating mode, whether the
+/// reference is enabled, and the gain.
+///
+/// The inverse of `encode_control()`. The reset bit and the unused low bits are
+/// ignored.
+pub fn decode_control(word: u16) -> (OperatingMode, bool, Gain) {
+    let mode = match (word >> 13) & 0b11 {
+        0x00 => OperatingMode::NormalMode,
+        0x01 => OperatingMode::Output1kImpedance,
+        0x02 => OperatingMode::Output100kImpedance,
+        _ => OperatingMode::OutputTristate,
+    };
+    let gain = if word & (1 << 11) == 0 {
+        Gain::X1
+    } else {
+        Gain::X2
+    };
+
+    (mode, word & (1 << 12) == 0, gain)
+}
+
+/// Control register state after a reset.
+///
+/// Models without an internal reference treat it as disabled, even though the
+/// register bit reads as enabled.
+fn power_on_control(model: Model) -> ControlRegister {
+    ControlRegister {
+        reference_enabled: model.has_internal_reference(),
+        ..ControlRegister::default()
+    }
+}
+
+/// Check whether a bus error is a NAK, such as the one the chip gives when it resets.
+fn is_nak<E: embedded_hal::i2c::Error>(error: &E) -> bool {
+    matches!(error.kind(), ErrorKind::NoAcknowledge(_))
+}
+
+/// Whether a bus error may go away if the transaction is repeated.
+fn is_transient<E: embedded_hal::i2c::Error>(error: &E) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::NoAcknowledge(_) | ErrorKind::ArbitrationLoss
+    )
+}
+
+/// Round to the nearest whole number, with halfway values rounded up.
+///
+/// Every float conversion to a code rounds with this, so they agree on the
+/// code for a voltage. NaN is kept.
+#[cfg(all(feature = "float", not(feature = "libm")))]
+pub(crate) fn round_nearest(exact: f32) -> f32 {
+    if exact.is_nan() {
+        return exact;
+    }
+
+    // Casts truncate towards zero, so step down for negative values
+    let shifted = exact + 0.5;
+    let truncated = shifted as i64 as f32;
+    if truncated > shifted {
+        truncated - 1.0
+    } else {
+        truncated
+    }
+}
+
+/// Round to the nearest whole number using `libm`, with halfway values rounded
+/// away from zero, which is up for valid codes.
+///
+/// Every float conversion to a code rounds with this, so they agree on the
+/// code for a voltage. NaN is kept.
+#[cfg(feature = "libm")]
+pub(crate) fn round_nearest(exact: f32) -> f32 {
+    libm::roundf(exact)
+}
+
+/// Round a scaled voltage to a code as `mode` says.
+///
+/// Float to int casts saturate, so negative values give zero, and NaN becomes
+/// zero too.
+#[cfg(all(feature = "float", not(feature = "libm")))]
+fn round_code(exact: f32, mode: RoundMode) -> u32 {
+    // Casts truncate, which rounds down for the values that matter
+    match mode {
+        RoundMode::Nearest => round_nearest(exact) as u32,
+        RoundMode::Floor => exact as u32,
+        RoundMode::Ceil => {
+            let truncated = exact as u32;
+            truncated.saturating_add(((truncated as f32) < exact) as u32)
+        }
+    }
+}
+
+/// Round a scaled voltage to a code as `mode` says, using `libm`.
+///
+/// Float to int casts saturate, so negative values give zero, and NaN becomes
+/// zero too.
+#[cfg(feature = "libm")]
+fn round_code(exact: f32, mode: RoundMode) -> u32 {
+    match mode {
+        RoundMode::Nearest => round_nearest(exact) as u32,
+        RoundMode::Floor => libm::floorf(exact) as u32,
+        RoundMode::Ceil => libm::ceilf(exact) as u32,
+    }
+}
+
+/// Scan a range of addresses for devices that acknowledge.
+///
+/// Each address gets a zero-length write, and the addresses that acknowledge are
+/// yielded in order. Bus errors other than a NAK are yielded as errors. Not every
+/// HAL supports zero-length writes.
+pub fn scan<I2C: I2c>(
+    i2c: &mut I2C,
+    addresses: RangeInclusive<u8>,
+) -> impl Iterator<Item = Result<u8, Error<I2C::Error>>> + '_ {
+    addresses.filter_map(|addr| match i2c.write(addr, &[]) {
+        Ok(()) => Some(Ok(addr)),
+        Err(error) if is_nak(&error) => None,
+        Err(error) => Some(Err(Error::I2c(error))),
+    })
+}
+
+/// Length in bytes of every write the driver sends.
+///
+/// A frame is laid out as:
+///
+/// | Byte | Contents                                     |
+/// |------|----------------------------------------------|
+/// | 0    | Command byte, the command in the high nibble |
+/// | 1    | Data word bits 15 to 8                       |
+/// | 2    | Data word bits 7 to 0                        |
+///
+/// The data word is big-endian and MSB-first. Codes for 12 and 14-bit parts
+/// are left-justified in it, shifted up by 4 and 2 bits, so the unused low bits
+/// are zero and a code's top bit is always bit 15.
+pub const FRAME_LEN: usize = 3;
+
+/// Build the bytes sent for a command: the command byte then the big-endian data word.
+///
+/// `data` is sent as-is, so codes for 12 and 14-bit parts must already be
+/// left-justified. See `FRAME_LEN` for the layout, and
+/// `AdafruitAD569x::code_frame()` to build a code write.
+pub const fn frame(command: Command, data: u16) -> [u8; FRAME_LEN] {
+    let [high_byte, low_byte] = data.to_be_bytes();
+
+    [command.bits(), high_byte, low_byte]
+}
+
+/// Code for a voltage in millivolts at a fixed reference, usable in `const`
+/// contexts such as tables of preset codes.
+///
+/// `gain_x2` doubles full scale like `Gain::X2`, and `bits` is the resolution of
+/// the part. This is the integer conversion `write_millivolts()` makes, before
+/// the calibration: the voltage is rounded to the nearest code with halfway
+/// values rounded up, and voltages above full scale clamp to the top code like
+/// `OverflowPolicy::Clamp`.
+///
+/// # Panics
+///
+/// Panics if `vref_mv` is zero or `bits` isn't between 1 and 16, which fails
+/// the build when evaluated in a `const`.
+pub const fn code_for_voltage_const(vref_mv: u32, gain_x2: bool, bits: u8, target_mv: u32) -> u16 {
+    assert!(vref_mv != 0, "reference voltage must not be zero");
+    assert!(bits >= 1 && bits <= 16, "resolution must be 1 to 16 bits");
+
+    let full_scale = vref_mv as u64 * if gain_x2 { 2 } else { 1 };
+    let span = 1u64 << bits;
+    let code = millivolts_to_code(full_scale, span, target_mv);
+
+    if code > span - 1 {
+        (span - 1) as u16
+    } else {
+        code as u16
+    }
+}
+
+/// Nearest code to `mv` for a full scale and code span, rounding halfway values
+/// up. The result isn't limited to the code range.
+const fn millivolts_to_code(full_scale_mv: u64, span: u64, mv: u32) -> u64 {
+    (mv as u64 * span + full_scale_mv / 2) / full_scale_mv
+}
+
+/// Function called by the driver after each bus write
+///
+/// It receives the command byte, the 16-bit data word, and the kind of bus error
+/// if the write failed.
+pub type Observer = fn(command: u8, data: u16, result: Result<(), ErrorKind>);
+
+/// Driver for an AD569x DAC on an I2C bus.
+///
+/// Every operation is a single bus transaction, so the bus is never held between
+/// calls. Several devices can share a bus through `embedded-hal-bus` wrappers:
+///
+/// ```
+/// use core::cell::RefCell;
+///
+/// use ad569x::{AdafruitAD569x, Error};
+/// use embedded_hal::i2c::I2c;
+/// use embedded_hal_bus::i2c::RefCellDevice;
+///
+/// fn set_both<I2C: I2c>(i2c: I2C) -> Result<I2C, Error<I2C::Error>> {
+///     let bus = RefCell::new(i2c);
+///     let mut first = AdafruitAD569x::new(RefCellDevice::new(&bus), 0x4C);
+///     let mut second = AdafruitAD569x::new(RefCellDevice::new(&bus), 0x4E);
+///
+///     first.begin()?;
+///     second.begin()?;
+///     first.write_update_dac(0x8000)?;
+///     second.write_update_dac(0x4000)?;
+///
+///     Ok(bus.into_inner())
+/// }
+/// ```
+pub struct AdafruitAD569x<I2C, LDAC = NoPin, RST = NoPin, SHDN = NoPin> {
     i2c: I2C,
+    ldac: LDAC,
+    reset_pin: RST,
+    shutdown_pin: SHDN,
+    output_enabled: bool,
+    disabled_output_policy: DisabledOutputPolicy,
     addr: u8,
+    model: Model,
+    config: Config,
+    control: Option<ControlRegister>,
+    input_code: Option<u16>,
+    dac_code: Option<u16>,
+    input_pending: bool,
+    calibration: Calibration,
+    overflow_policy: OverflowPolicy,
+    retries: u8,
+    monotonic: Option<Direction>,
+    output_stage: OutputStage,
+    transconductance_microamps_per_volt: i32,
+    initialized: bool,
+    observer: Option<Observer>,
+    last_control_word: Option<u16>,
+    last_dac_word: Option<u16>,
+    verify_writes: bool,
+    lazy_sync: bool,
+    allow_powered_down_writes: bool,
+    drift_table: &'static [DriftPoint],
+    accumulated: Option<i64>,
+    max_slew_code_per_ms: Option<u32>,
+    rate_limit: Option<RateLimit>,
+    presets: [Option<u16>; PRESET_COUNT],
+    last_write_us: Option<u32>,
+    stats: DriverStats,
 }
 
 impl<I2C: I2c> AdafruitAD569x<I2C> {
+    /// Create a driver for an AD5693R assuming the 2.5V internal reference.
     pub fn new(i2c: I2C, addr: u8) -> Self {
-        Self { i2c, addr }
+        Self::with_model(i2c, addr, Model::AD5693R)
+    }
+
+    /// Create a driver for the part with an internal reference and the given
+    /// resolution, assuming the 2.5V internal reference.
+    pub fn with_resolution(i2c: I2C, addr: u8, resolution: Resolution) -> Self {
+        let model = match resolution {
+            Resolution::Bits12 => Model::AD5691R,
+            Resolution::Bits14 => Model::AD5692R,
+            Resolution::Bits16 => Model::AD5693R,
+        };
+
+        Self::with_model(i2c, addr, model)
+    }
+
+    /// Create a driver for the given model.
+    ///
+    /// Parts with an internal reference assume it is on at `INTERNAL_VREF_MV`. The
+    /// AD5693 has the reference disabled in its `Config`, and enabling it returns
+    /// `Error::ReferenceNotAvailable`. Its reference voltage is left at zero, so the
+    /// voltage conversions return `Error::InvalidConfig` until the external
+    /// reference voltage is set with `with_vref()`.
+    pub fn with_model(i2c: I2C, addr: u8, model: Model) -> Self {
+        debug_assert!(addr <= 0x7F, "I2C address must be 7-bit");
+
+        let config = if model.has_internal_reference() {
+            Config::default()
+        } else {
+            Config::default().with_reference(false).with_vref(0)
+        };
+
+        Self {
+            i2c,
+            ldac: NoPin,
+            reset_pin: NoPin,
+            shutdown_pin: NoPin,
+            output_enabled: true,
+            disabled_output_policy: DisabledOutputPolicy::default(),
+            addr,
+            model,
+            config,
+            control: None,
+            input_code: None,
+            dac_code: None,
+            input_pending: false,
+            calibration: Calibration::default(),
+            overflow_policy: OverflowPolicy::default(),
+            retries: 0,
+            monotonic: None,
+            output_stage: OutputStage::default(),
+            transconductance_microamps_per_volt: 0,
+            initialized: false,
+            observer: None,
+            last_control_word: None,
+            last_dac_word: None,
+            verify_writes: false,
+            lazy_sync: false,
+            allow_powered_down_writes: false,
+            drift_table: &[],
+            accumulated: None,
+            max_slew_code_per_ms: None,
+            rate_limit: None,
+            presets: [None; PRESET_COUNT],
+            last_write_us: None,
+            stats: DriverStats::default(),
+        }
+    }
+
+    /// Create a driver for `FEATURE_MODEL` at the default address.
+    ///
+    /// The model, and so the resolution, reference, and voltage math, come from
+    /// the `model-*` feature the crate was built with. See `with_model()`.
+    pub fn default_for_feature(i2c: I2C) -> Self {
+        Self::with_model(i2c, Address::default().into(), FEATURE_MODEL)
+    }
+
+    /// Create a driver for an AD5693R at the address set by the A0 strapping.
+    pub fn with_address(i2c: I2C, address: Address) -> Self {
+        Self::new(i2c, address.into())
+    }
+
+    /// Create a driver for an AD5693R and initialize it with `config`.
+    ///
+    /// Returns `Error::InvalidConfig` without touching the bus if the settings
+    /// can't be used together, such as a zero reference voltage.
+    pub fn with_config(i2c: I2C, addr: u8, config: Config) -> Result<Self, Error<I2C::Error>> {
+        if !config.is_valid() {
+            return Err(Error::InvalidConfig);
+        }
+
+        let mut dac = Self::new(i2c, addr);
+        dac.set_config(config);
+        dac.begin()?;
+
+        Ok(dac)
+    }
+
+    /// Create a driver for an AD5693R that something else has already set up
+    /// with `config`.
+    ///
+    /// Nothing is written, and the control register is assumed to hold
+    /// `config`'s mode, reference, and gain, as when a bootloader configured the
+    /// chip. The output code stays unknown until it is written or read. If the
+    /// chip isn't actually configured as `config` says, the cached state is
+    /// silently wrong; use `restore_from_device()` to read it instead.
+    pub fn new_assume_initialized(i2c: I2C, addr: u8, config: Config) -> Self {
+        let mut dac = Self::new(i2c, addr);
+        dac.set_config(config);
+        dac.control = Some(ControlRegister {
+            mode: config.mode,
+            reference_enabled: config.reference_enabled,
+            gain: config.gain,
+        });
+        dac.initialized = true;
+
+        dac
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Set the reference voltage, in millivolts, used by the voltage conversions.
+    pub fn with_vref(mut self, vref_millivolts: u32) -> Self {
+        self.config.vref_millivolts = vref_millivolts;
+        self
+    }
+
+    /// Retry bus writes up to `count` times on transient errors.
+    ///
+    /// A write is retried when it fails with `ErrorKind::NoAcknowledge` or
+    /// `ErrorKind::ArbitrationLoss`. Other errors are returned immediately. The
+    /// reset command is never retried, since the chip NAKs it as it resets.
+    /// Defaults to no retries.
+    pub fn with_retries(mut self, count: u8) -> Self {
+        self.retries = count;
+        self
+    }
+
+    /// Set a function to call after each bus write, or `None` to stop.
+    ///
+    /// Useful for routing bus activity into a logging framework other than defmt.
+    /// Each retry from `with_retries()` is reported as a separate write.
+    pub fn set_observer(&mut self, observer: Option<Observer>) {
+        self.observer = observer;
+    }
+
+    /// Borrow the I2C bus for transactions the driver doesn't model.
+    ///
+    /// The driver caches the chip's registers, so writing this chip's registers
+    /// through the bus leaves the cached state out of date. Keep to other devices
+    /// or read-only commands, or call `reset()` afterwards.
+    pub fn bus_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    /// Destroy the driver and return the I2C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
     }
 
     /// Initialize the AD569x chip for communication.
     ///
-    /// Will perform a soft reset and configure for normal mode,
-    /// with Vref on, and 1x gain output.
-    pub fn begin(&mut self) -> Result<(), I2C::Error> {
-        self.reset()?;
-        self.set_mode(OperatingMode::NormalMode, true, false)?;
+    /// Will perform a soft reset and apply the driver's `Config`. By default that
+    /// configures for normal mode, with Vref on, and 1x gain output.
+    ///
+    /// If the config has a power-on output code, it is preloaded into the input
+    /// register before the control register is written, then moved to the output
+    /// with an update. The soft reset still drives zero-scale until then.
+    ///
+    /// Returns `Error::ReferenceNotAvailable` without touching the bus if the
+    /// config enables the reference on a model without one. An out-of-range
+    /// power-on output code under `OverflowPolicy::Error` also returns an error
+    /// without touching the bus, as does a power-on output code with a
+    /// power-down mode, like `begin_with_output()`. The update is otherwise
+    /// checked and gated like `update_dac()`, after the reset.
+    pub fn begin(&mut self) -> Result<(), Error<I2C::Error>> {
+        let output = match self.config.power_on_output_code {
+            Some(code) => Some(self.limit_code(code as i64)?),
+            None => None,
+        };
+        if output.is_some()
+            && self.config.mode != OperatingMode::NormalMode
+            && !self.allow_powered_down_writes
+        {
+            return Err(Error::NotInOutputMode);
+        }
+
+        self.begin_with(output)
+    }
+
+    /// Initialize the AD569x chip with `config`, leaving the output at `initial_code`.
+    ///
+    /// The driver keeps `config` for later calls to `begin()`, and `initial_code`
+    /// takes the place of its power-on output code. This makes three bus writes,
+    /// each a command byte and a big-endian data word:
+    ///
+    /// 1. `[0x40, 0x80, 0x00]`, the soft reset, which sets the output to zero-scale.
+    /// 2. `[0x40, ..]`, the control word from `config`.
+    /// 3. `[0x30, ..]`, `initial_code` written to the input and DAC registers.
+    ///
+    /// An invalid `config`, or an out-of-range `initial_code` under
+    /// `OverflowPolicy::Error`, returns an error without touching the bus. So
+    /// does a `config` with a power-down mode, returning `Error::NotInOutputMode`
+    /// unless `allow_write_while_powered_down()` is set. The slew limit, the
+    /// monotonic guard, and the disabled output policy are only checked at the
+    /// third write, after the reset.
+    pub fn begin_with_output(
+        &mut self,
+        config: Config,
+        initial_code: u16,
+    ) -> Result<(), Error<I2C::Error>> {
+        if !config.is_valid() {
+            return Err(Error::InvalidConfig);
+        }
+        self.check_reference(config.reference_enabled)?;
+        if config.mode != OperatingMode::NormalMode && !self.allow_powered_down_writes {
+            return Err(Error::NotInOutputMode);
+        }
+        let code = self.limit_code(initial_code as i64)?;
+
+        self.set_config(config);
+        self.begin_with(None)?;
+        self.write_update_dac(code)
+    }
+
+    /// Re-assert the cached state without resetting the chip, so the output
+    /// doesn't drop.
+    ///
+    /// Unlike `begin()`, there is no soft reset. The cached DAC code is rewritten
+    /// to the input and DAC registers, then the cached control register is rewritten,
+    /// or the one from the config if the driver hasn't written it yet. Both writes
+    /// are made even if the cache says they are unchanged, which recovers a chip
+    /// whose registers were disturbed.
+    ///
+    /// Returns `Error::UnknownState` without writing if the output code isn't
+    /// known.
+    pub fn reinit_preserving_output(&mut self) -> Result<(), Error<I2C::Error>> {
+        let code = self.dac_code.ok_or(Error::UnknownState)?;
+        let control = self.control.unwrap_or(ControlRegister {
+            mode: self.config.mode,
+            reference_enabled: self.config.reference_enabled,
+            gain: self.config.gain,
+        });
+
+        self.rewrite_state(Some(code), Some(control))?;
+        self.initialized = true;
 
         Ok(())
     }
 
-    /// Write a 16-bit value to the DAC register... does NOT output it!
+    /// Send a NOP command, which doesn't change any register.
     ///
-    /// This function writes a 16-bit value to the input register of the AD569x chip.
-    /// The data does not appear on the output of the DAC till you run `update_dac()`!
-    pub fn write_dac(&mut self, value: u16) -> Result<(), I2C::Error> {
-        self.write(Command::WriteInput, value)
+    /// Useful as a keep-alive or presence poll. See `probe()` to tell a missing
+    /// chip apart from other bus errors.
+    pub fn nop(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write(Command::NOP, 0x00)
     }
 
-    /// Update the DAC register from the input register.
+    /// Check whether the chip acknowledges at its address.
     ///
-    /// This function sends the UPDATE_DAC command to the AD569x chip to update
-    /// the DAC register based on the value stored in the input register.
-    pub fn update_dac(&mut self) -> Result<(), I2C::Error> {
-        self.write(Command::UpdateDAC, 0x00)
+    /// Sends a NOP command, returning `false` if it is not acknowledged. Bus errors
+    /// other than a NAK are still returned.
+    pub fn probe(&mut self) -> Result<bool, Error<I2C::Error>> {
+        match self.nop() {
+            Ok(()) => Ok(true),
+            Err(Error::I2c(error)) if is_nak(&error) => Ok(false),
+            Err(error) => Err(error),
+        }
     }
 
-    /// Write a 16-bit value to the input register and update the DAC
-    /// register.
+    /// Guess the model from how many low bits of the input register are kept.
     ///
-    /// This function writes a 16-bit value to the input register and then updates
-    /// the DAC register of the AD569x chip in a single operation
-    pub fn write_update_dac(&mut self, value: u16) -> Result<(), I2C::Error> {
-        self.write(Command::WriteDACAndInput, value)
+    /// Writes `0xFFFF` to the input register and reads it back. The 12 and
+    /// 14-bit parts read the unused low bits as zero, which gives the
+    /// resolution. The output doesn't change, and the input register's previous
+    /// word is written back afterwards. The 16-bit parts can't be told apart, so
+    /// they are reported as the driver's model if it is one, or else the AD5693R.
+    ///
+    /// Returns `Error::VerifyMismatch` with the word read if it matches no model.
+    pub fn detect_model(&mut self) -> Result<Model, Error<I2C::Error>> {
+        let previous = self.read(Command::WriteInput)?;
+        self.write(Command::WriteInput, 0xFFFF)?;
+        let pattern = self.read(Command::WriteInput);
+        self.write(Command::WriteInput, previous)?;
+
+        let model = match pattern? {
+            0xFFF0 => Model::AD5691R,
+            0xFFFC => Model::AD5692R,
+            0xFFFF if self.model.resolution() == Resolution::Bits16 => self.model,
+            0xFFFF => Model::AD5693R,
+            actual => {
+                return Err(Error::VerifyMismatch {
+                    expected: 0xFFFF,
+                    actual,
+                });
+            }
+        };
+
+        Ok(model)
     }
 
-    /// Soft-reset the AD569x chip.
+    /// The model the driver was created for.
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// The 7-bit I2C address the driver talks to.
+    pub fn address(&self) -> u8 {
+        self.addr
+    }
+
+    /// Talk to the chip at a new address, such as after its A0 strap changes.
     ///
-    /// This function writes 0x8000 to the control register of the AD569x chip
-    /// to perform a reset operation. Resets the DAC to zero-scale and
-    /// resets the input, DAC, and control registers to their default values.
+    /// The cached state is kept, as it is still the same chip. Use
+    /// `Address::into()` for the strapped addresses.
+    pub fn set_address(&mut self, addr: u8) {
+        debug_assert!(addr <= 0x7F, "I2C address must be 7-bit");
+
+        self.addr = addr;
+    }
+
+    /// Largest code accepted by the DAC for the model's resolution.
+    pub fn max_code(&self) -> u16 {
+        self.model.resolution().max_code()
+    }
+
+    /// Saturate a code to the range `0..=max_code()`.
+    pub fn clamp_code(&self, code: i32) -> u16 {
+        code.clamp(0, self.max_code() as i32) as u16
+    }
+
+    /// Add a signed delta to a code, saturating at zero and `max_code()`.
+    pub fn offset_code(&self, base: u16, delta: i32) -> u16 {
+        self.clamp_code((base as i32).saturating_add(delta))
+    }
+
+    /// Code in the DAC register, as last written by the driver.
     ///
-    /// Note: The original driver implies the write will return an error as it "resets before it naks".
-    /// What that means, I have no idea.
-    pub fn reset(&mut self) -> Result<(), I2C::Error> {
-        self.write(Command::WriteControl, 0x8000)
+    /// `None` until the driver has written or reset the chip.
+    pub fn current_code(&self) -> Option<u16> {
+        self.dac_code
     }
 
-    /// Set the operating mode, reference, and gain for the AD569x chip.
+    /// Gain last written by the driver, or `None` until it has configured or reset the chip.
+    pub fn current_gain(&self) -> Option<Gain> {
+        self.control.map(|control| control.gain)
+    }
+
+    /// Operating mode last written by the driver, or `None` until it has configured
+    /// or reset the chip.
+    pub fn current_mode(&self) -> Option<OperatingMode> {
+        self.control.map(|control| control.mode)
+    }
+
+    /// Reference setting last written by the driver, or `None` until it has
+    /// configured or reset the chip.
+    pub fn reference_enabled(&self) -> Option<bool> {
+        self.control.map(|control| control.reference_enabled)
+    }
+
+    /// The data word of the last control register write, as sent on the bus.
     ///
-    /// This function writes to the control register of the AD569x chip to set
-    /// the operating mode, enable or disable the reference, and set the gain.
-    pub fn set_mode(
-        &mut self,
-        mode: OperatingMode,
-        enable_ref: bool,
-        gain_2x: bool,
-    ) -> Result<(), I2C::Error> {
-        let data =
-            0x0u16 | ((mode as u16) << 13) | ((!enable_ref as u16) << 12) | (gain_2x as u16) << 11;
+    /// `None` until the driver first writes the control register. A soft reset
+    /// counts, with the reset bit set.
+    pub fn last_control_word(&self) -> Option<u16> {
+        self.last_control_word
+    }
 
-        self.write(Command::WriteControl, data)
+    /// The data word of the last input register write, as sent on the bus.
+    ///
+    /// This is the left-justified word, so it differs from the code on 12 and
+    /// 14-bit parts. `None` until the driver first writes a code.
+    pub fn last_dac_word(&self) -> Option<u16> {
+        self.last_dac_word
     }
-}
 
-impl<I2C: I2c> AdafruitAD569x<I2C> {
-    fn write(&mut self, command: Command, data: u16) -> Result<(), I2C::Error> {
-        let [high_byte, low_byte] = data.to_be_bytes();
+    /// Read back every code write to confirm it, for noisy buses.
+    ///
+    /// When enabled, `write_dac()` reads back the input register and
+    /// `write_update_dac()` reads back the DAC register, returning
+    /// `Error::VerifyMismatch` if the code differs. This doubles the number of
+    /// bus transactions for each write. `write_update_many()` only reads back its
+    /// last code. Off by default.
+    pub fn verify_every_write(&mut self, enabled: bool) {
+        self.verify_writes = enabled;
+    }
 
-        self.i2c
-            .write(self.addr, &[command as u8, high_byte, low_byte])
+    /// Read the chip's state back the first time an operation needs it.
+    ///
+    /// When enabled and the cached state isn't fully known, the first of
+    /// `write_update_if_changed()`, the mode, gain, and reference setters,
+    /// `ramp_to()`, `fade_to()`, and `write_update_dac_slewed()` runs
+    /// `restore_from_device()` before it proceeds. This happens at most once
+    /// after enabling it. If the readback fails, the state is left unknown and
+    /// the operation carries on as it would without lazy sync. Off by default,
+    /// so the driver never reads the chip unasked.
+    pub fn lazy_sync(&mut self, enabled: bool) {
+        self.lazy_sync = enabled;
+    }
+
+    /// Allow writes that change the output while the chip is powered down.
+    ///
+    /// By default, `write_update_dac()`, `update_dac()`, and the other writes
+    /// that change the output return `Error::NotInOutputMode` when the cached
+    /// mode isn't `OperatingMode::NormalMode`, as the output won't follow them
+    /// until `wake()`. Allowing them lets the DAC register be loaded ahead of
+    /// waking. Writes made before the mode is known are always allowed.
+    pub fn allow_write_while_powered_down(&mut self, allowed: bool) {
+        self.allow_powered_down_writes = allowed;
+    }
+
+    /// Set how out-of-range codes and voltages are handled by the writes.
+    ///
+    /// Defaults to `OverflowPolicy::Error`.
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow_policy = policy;
+    }
+
+    /// Only allow output changes in one direction until `clear_monotonic()`.
+    ///
+    /// While the guard is set, `write_update_dac()`, `update_dac()`, and
+    /// `update_via_ldac()` return `Error::MonotonicityViolation` without writing
+    /// if the new code would move the output against `direction`. Writes made
+    /// before the output code is known aren't checked.
+    pub fn begin_monotonic(&mut self, direction: Direction) {
+        self.monotonic = Some(direction);
+    }
+
+    /// Remove the guard set by `begin_monotonic()`.
+    pub fn clear_monotonic(&mut self) {
+        self.monotonic = None;
+    }
+
+    /// Limit how far the output may move in a millisecond, or `None` for no limit.
+    ///
+    /// While set, `write_update_dac()`, `update_dac()`, and `update_via_ldac()`
+    /// return `Error::SlewLimitExceeded` without writing if the output would move
+    /// more than `max_code_per_ms` codes at once, as they can't wait between
+    /// steps. They also return `Error::UnknownState` while the output code isn't
+    /// known. Use `write_update_dac_slewed()` to make larger moves in steps.
+    pub fn set_max_slew(&mut self, max_code_per_ms: Option<u32>) {
+        self.max_slew_code_per_ms = max_code_per_ms;
+    }
+
+    /// Write a 16-bit value to the DAC register... does NOT output it!
+    ///
+    /// This function writes a 16-bit value to the input register of the AD569x chip.
+    /// The data does not appear on the output of the DAC till you run `update_dac()`!
+    ///
+    /// Values above `max_code()` are handled by the overflow policy.
+    pub fn write_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        let code = self.write_code(Command::WriteInput, value)?;
+        self.input_code = Some(code);
+        self.input_pending = true;
+
+        if self.verify_writes {
+            self.check_readback(code, Self::read_input)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add to a code kept in software until `commit()` writes it.
+    ///
+    /// The first call starts from the code in the input register, or zero if it
+    /// isn't known. Nothing is written to the chip until `commit()`.
+    pub fn accumulate(&mut self, delta: i32) {
+        let base = self
+            .accumulated
+            .unwrap_or(self.input_code.unwrap_or(0) as i64);
+
+        self.accumulated = Some(base.saturating_add(delta as i64));
+    }
+
+    /// Write the code built up by `accumulate()` to the input register.
+    ///
+    /// The code is written with `write_dac()`, so it reaches the output on the
+    /// next `update_dac()`. A code that left `0..=max_code()` is handled by the
+    /// overflow policy, returning `Error::ValueOutOfRange` without writing by
+    /// default. The accumulated code is kept if the commit fails. Does nothing
+    /// if nothing has been accumulated.
+    pub fn commit(&mut self) -> Result<(), Error<I2C::Error>> {
+        let Some(accumulated) = self.accumulated else {
+            return Ok(());
+        };
+        let code = self.limit_code(accumulated)?;
+
+        self.write_dac(code)?;
+        self.accumulated = None;
+
+        Ok(())
+    }
+
+    /// Update the DAC register from the input register.
+    ///
+    /// This function sends the UPDATE_DAC command to the AD569x chip to update
+    /// the DAC register based on the value stored in the input register.
+    ///
+    /// Returns `UpdateKind::Stale` if there was no `write_dac()` since the last
+    /// update, which usually means the input register is stale. The update is
+    /// still made, with a defmt warning.
+    ///
+    /// Returns `Error::NotInOutputMode` without writing if the chip is powered
+    /// down, unless `allow_write_while_powered_down()` is set. The same goes for
+    /// `write_update_dac()` and the other writes that change the output. Returns
+    /// `Error::UnknownState` without writing if the input register's code isn't
+    /// known, such as after an interrupted write, as the update can't be checked.
+    pub fn update_dac(&mut self) -> Result<UpdateKind, Error<I2C::Error>> {
+        let kind = self.update_kind();
+        self.check_update()?;
+
+        self.update_from_input()?;
+
+        Ok(kind)
+    }
+
+    /// Update the DAC register from the input register only if the chip's two
+    /// registers differ.
+    ///
+    /// Both registers are read with `read_both()`, so this catches input
+    /// register writes made by another bus master, which the cached codes don't.
+    /// Returns whether an update was made. The cached codes are set from the
+    /// registers read. The update is checked and gated like `update_dac()`, but
+    /// doesn't need a `write_dac()` first.
+    pub fn update_if_stale(&mut self) -> Result<bool, Error<I2C::Error>> {
+        let (input, dac) = self.read_both()?;
+        self.input_code = Some(input);
+        self.dac_code = Some(dac);
+        self.input_pending = input != dac;
+        if !self.input_pending {
+            return Ok(false);
+        }
+
+        self.check_output_change(input)?;
+        self.gate_output()?;
+        self.update_from_input()?;
+
+        Ok(true)
+    }
+
+    /// Whether a `write_dac()` is waiting for `update_dac()`.
+    pub fn input_pending(&self) -> bool {
+        self.input_pending
+    }
+
+    /// Write a 16-bit value to the input register and update the DAC
+    /// register.
+    ///
+    /// This function writes a 16-bit value to the input register and then updates
+    /// the DAC register of the AD569x chip in a single operation
+    ///
+    /// Values above `max_code()` are handled by the overflow policy.
+    ///
+    /// If the write fails on the bus, the input register is read back to show
+    /// whether the chip took the code, and `Error::WriteInterrupted` is returned
+    /// with the code read, or `None` if the readback failed too. The cached output
+    /// code is unknown afterwards.
+    pub fn write_update_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        self.check_output_change(self.limit_code(value as i64)?)?;
+        self.gate_output()?;
+        let code = match self.write_code(Command::WriteDACAndInput, value) {
+            Err(Error::I2c(error)) => return Err(self.recover_interrupted(error)),
+            result => result?,
+        };
+        self.input_code = Some(code);
+        self.dac_code = Some(code);
+        self.input_pending = false;
+
+        if self.verify_writes {
+            self.check_readback(code, Self::read_dac)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write and update the DAC only if `value` differs from the last code written.
+    ///
+    /// Returns whether a write was made. The first call always writes, as there is
+    /// no known code to compare against.
+    pub fn write_update_if_changed(&mut self, value: u16) -> Result<bool, Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let code = Some(self.limit_code(value as i64)?);
+        if self.dac_code == code && self.input_code == code {
+            return Ok(false);
+        }
+
+        self.write_update_dac(value)?;
+
+        Ok(true)
+    }
+
+    /// Write and update the DAC, then read the DAC register back to confirm it.
+    ///
+    /// Returns `Error::VerifyMismatch` if the code read back differs from the one
+    /// written. Values above `max_code()` are handled by the overflow policy, and
+    /// a clamped code is the one compared.
+    pub fn write_verified(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        let expected = self.limit_code(value as i64)?;
+        self.write_update_dac(expected)?;
+
+        if self.verify_writes {
+            // Already read back by write_update_dac()
+            return Ok(());
+        }
+        self.check_readback(expected, Self::read_dac)
+    }
+
+    /// Write and verify a code like `write_verified()`, trying again until the
+    /// readback matches or `max_attempts` writes have been made.
+    ///
+    /// Returns the number of attempts made, counting the one that succeeded.
+    /// At least one attempt is made, even if `max_attempts` is `0`. Only bus
+    /// errors and mismatched readbacks are tried again; errors such as
+    /// `Error::ValueOutOfRange` are returned straight away.
+    ///
+    /// Returns the error from the last attempt, such as `Error::VerifyMismatch`,
+    /// once the attempts are used up.
+    pub fn write_confirmed(
+        &mut self,
+        code: u16,
+        max_attempts: u8,
+    ) -> Result<u8, Error<I2C::Error>> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.write_verified(code) {
+                Ok(()) => return Ok(attempts),
+                Err(
+                    Error::I2c(_) | Error::WriteInterrupted { .. } | Error::VerifyMismatch { .. },
+                ) if attempts < max_attempts => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Check that the input register takes writes, without changing the output.
+    ///
+    /// Writes two alternating bit patterns to the input register, reading each
+    /// back, then rewrites the code the input register held before. The DAC
+    /// register isn't updated, so the output never moves.
+    ///
+    /// Returns `Error::StuckRegister` if a pattern doesn't read back, after
+    /// restoring the input register. A bus error partway through is returned the
+    /// same way, once the restore has been tried. If the restore fails, the cached
+    /// input register code becomes unknown.
+    pub fn verify_writable(&mut self) -> Result<(), Error<I2C::Error>> {
+        let original = self.read_input()?;
+        let pending = self.input_pending;
+
+        let mut result = Ok(());
+        for pattern in [0x5555, 0xAAAA] {
+            let expected = pattern & self.max_code();
+            result = self
+                .write_code(Command::WriteInput, expected)
+                .and_then(|_| self.read_input())
+                .and_then(|actual| {
+                    if actual != expected {
+                        return Err(Error::StuckRegister { expected, actual });
+                    }
+
+                    Ok(())
+                });
+            if result.is_err() {
+                break;
+            }
+        }
+
+        let restored = self.write_dac(original);
+        self.input_pending = pending;
+        if restored.is_err() {
+            self.input_code = None;
+        }
+
+        result.and(restored)
+    }
+
+    /// Write and update the DAC to zero-scale.
+    pub fn set_zero_scale(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_update_dac(0)
+    }
+
+    /// Write and update the DAC to mid-scale, `max_code() / 2 + 1`.
+    pub fn set_mid_scale(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_update_dac(self.max_code() / 2 + 1)
+    }
+
+    /// Write and update the DAC to full scale, `max_code()`.
+    pub fn set_full_scale(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_update_dac(self.max_code())
+    }
+
+    /// Write a fraction of full scale, in tenths of a percent, and update the DAC register.
+    ///
+    /// `1000` is full scale and `500` is mid-scale for any resolution. The code is
+    /// `per_mille * max_code() / 1000` rounded to the nearest code, with halfway
+    /// values rounded up. Values above `1000` clamp to full scale.
+    pub fn write_permille(&mut self, per_mille: u16) -> Result<(), Error<I2C::Error>> {
+        let per_mille = per_mille.min(1000) as u32;
+        let code = (per_mille * self.max_code() as u32 + 500) / 1000;
+
+        self.write_update_dac(code as u16)
+    }
+
+    /// Write a fraction of full scale, in hundredths of a percent, and update the
+    /// DAC register.
+    ///
+    /// `10000` is full scale and `5000` is mid-scale for any resolution. Uses
+    /// integer math only: the code is `centi * max_code() / 10000` rounded to the
+    /// nearest code, with halfway values rounded up. Values above `10000` are
+    /// handled by the overflow policy.
+    pub fn write_centipercent(&mut self, centi: u16) -> Result<(), Error<I2C::Error>> {
+        let code = if centi > 10000 {
+            self.limit_code(self.max_code() as i64 + 1)?
+        } else {
+            ((centi as u32 * self.max_code() as u32 + 5000) / 10000) as u16
+        };
+
+        self.write_update_dac(code)
+    }
+
+    /// Read back the code held in the input register.
+    ///
+    /// This function points the AD569x chip at the input register and reads its
+    /// 16-bit contents in a repeated-start transaction. The left-justified data word
+    /// is shifted down so the logical code is returned for sub-16-bit parts.
+    pub fn read_input(&mut self) -> Result<u16, Error<I2C::Error>> {
+        self.read_code(Command::WriteInput)
+    }
+
+    /// Read back the code held in the DAC register.
+    ///
+    /// This is the code currently driving the output. The left-justified data word
+    /// is shifted down so the logical code is returned for sub-16-bit parts.
+    pub fn read_dac(&mut self) -> Result<u16, Error<I2C::Error>> {
+        self.read_code(Command::WriteDACAndInput)
+    }
+
+    /// Read back the input and DAC register codes together, as `(input, dac)`.
+    ///
+    /// Both registers are read in one bus transaction, pointing at each in
+    /// turn with repeated starts between, so the two reads aren't split by
+    /// other traffic. The codes are right-justified like `read_input()` and
+    /// `read_dac()`.
+    pub fn read_both(&mut self) -> Result<(u16, u16), Error<I2C::Error>> {
+        let (mut input, mut dac) = ([0u8; 2], [0u8; 2]);
+
+        self.i2c
+            .transaction(
+                self.addr,
+                &mut [
+                    Operation::Write(&[Command::WriteInput.bits()]),
+                    Operation::Read(&mut input),
+                    Operation::Write(&[Command::WriteDACAndInput.bits()]),
+                    Operation::Read(&mut dac),
+                ],
+            )
+            .map_err(Error::I2c)?;
+
+        let resolution = self.model.resolution();
+        Ok((
+            resolution.unjustify(u16::from_be_bytes(input)),
+            resolution.unjustify(u16::from_be_bytes(dac)),
+        ))
+    }
+
+    /// Write a command with a raw 16-bit data word.
+    ///
+    /// The data is sent as-is, without left-justification or range checks. The
+    /// driver's cached state isn't updated, so it may no longer match the chip.
+    pub fn write_raw(&mut self, command: Command, data: u16) -> Result<(), Error<I2C::Error>> {
+        self.write(command, data)
+    }
+
+    /// Build the bytes a code write would send, without sending them.
+    ///
+    /// The code is limited by the overflow policy and left-justified for the
+    /// model's resolution, as `write_dac()` and `write_update_dac()` do. Nothing
+    /// is written and the cached state isn't changed.
+    pub fn code_frame(
+        &self,
+        command: Command,
+        code: u16,
+    ) -> Result<[u8; FRAME_LEN], Error<I2C::Error>> {
+        let code = self.limit_code(code as i64)?;
+
+        Ok(frame(command, self.model.resolution().justify(code)))
+    }
+
+    /// Read back and decode the control register.
+    ///
+    /// Useful to confirm the chip's configuration after a reset or brownout.
+    pub fn read_control(&mut self) -> Result<ControlRegister, Error<I2C::Error>> {
+        let (mode, reference_enabled, gain) = decode_control(self.read(Command::WriteControl)?);
+
+        Ok(ControlRegister {
+            mode,
+            reference_enabled,
+            gain,
+        })
+    }
+
+    /// Rebuild the cached chip state from the chip's registers.
+    ///
+    /// Reads the control, input, and DAC registers without writing anything, so
+    /// the output is left as it is. Use this instead of `begin()` when the chip
+    /// kept its state but the driver didn't, such as after the MCU wakes from deep
+    /// sleep. The cache is only updated once all three reads succeed.
+    pub fn restore_from_device(&mut self) -> Result<(), Error<I2C::Error>> {
+        let control = self.read_control()?;
+        let input_code = self.read_input()?;
+        let dac_code = self.read_dac()?;
+
+        self.control = Some(control);
+        self.input_code = Some(input_code);
+        self.dac_code = Some(dac_code);
+        self.input_pending = input_code != dac_code;
+
+        Ok(())
+    }
+
+    /// Write the cached state back to the chip.
+    ///
+    /// The write-direction counterpart to `restore_from_device()`. The cached DAC
+    /// code is written to the input and DAC registers, then the cached control
+    /// register is written, each even if the cache says it is unchanged. Either
+    /// is skipped if it isn't known. Useful after a hot-plug, or when the chip
+    /// may no longer match the driver.
+    pub fn sync_to_device(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.rewrite_state(self.dac_code, self.control)
+    }
+
+    /// Soft-reset the AD569x chip.
+    ///
+    /// This function writes `RESET_CONTROL_WORD` to the control register of the
+    /// AD569x chip to perform a reset operation. Resets the DAC to zero-scale and
+    /// resets the input, DAC, and control registers to their default values.
+    ///
+    /// The chip resets before it acknowledges the write, so a NAK is expected and
+    /// treated as success. Other bus errors are still returned.
+    pub fn reset(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.reset_with(RESET_CONTROL_WORD)
+    }
+
+    /// Soft-reset the chip with a custom control word, for experimenting with
+    /// revision quirks.
+    ///
+    /// Like `reset()`, but `control_word` is written in place of
+    /// `RESET_CONTROL_WORD`, and a NAK is still treated as success. The cached
+    /// state is reset to the power-on defaults whatever the word holds, so if it
+    /// might not reset the chip, follow it with `restore_from_device()`.
+    pub fn reset_with(&mut self, control_word: u16) -> Result<(), Error<I2C::Error>> {
+        trace!("reset {=u16:#06x}", control_word);
+
+        match self.write_once(Command::WriteControl, control_word) {
+            Err(Error::I2c(error)) if is_nak(&error) => self.last_control_word = Some(control_word),
+            Err(Error::I2c(error)) => {
+                self.stats.record_error(error.kind());
+                return Err(Error::I2c(error));
+            }
+            result => result?,
+        }
+        self.stats.record_write(Command::WriteControl);
+        self.reset_state();
+
+        Ok(())
+    }
+
+    /// Soft-reset the chip, then read back the control and DAC registers to
+    /// confirm they hold their reset values.
+    ///
+    /// Returns `Error::VerifyMismatch` if the decoded control word isn't
+    /// `POR_CONTROL_WORD`, or the DAC code isn't `POR_CODE`.
+    pub fn reset_and_verify(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.reset()?;
+
+        let control = self.read_control()?;
+        let actual = encode_control(control.mode, control.reference_enabled, control.gain);
+        if actual != POR_CONTROL_WORD {
+            return Err(Error::VerifyMismatch {
+                expected: POR_CONTROL_WORD,
+                actual,
+            });
+        }
+
+        let actual = self.read_dac()?;
+        if actual != POR_CODE {
+            return Err(Error::VerifyMismatch {
+                expected: POR_CODE,
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Set the operating mode, reference, and gain for the AD569x chip.
+    ///
+    /// This function writes to the control register of the AD569x chip to set
+    /// the operating mode, enable or disable the reference, and set the gain.
+    /// The write is skipped if the driver last wrote the same settings, but not
+    /// if it doesn't know the control register yet.
+    ///
+    /// Returns the control register state it replaced, so it can be restored
+    /// later, and whether it was written. That is the state last written by the
+    /// driver, or the power-on defaults if it hasn't configured the chip yet.
+    ///
+    /// Returns `Error::ReferenceNotAvailable` if the reference is enabled on a
+    /// model without one.
+    pub fn set_mode(
+        &mut self,
+        mode: OperatingMode,
+        enable_ref: bool,
+        gain: Gain,
+    ) -> Result<ControlChange, Error<I2C::Error>> {
+        trace!("set mode {} ref {} gain {}", mode, enable_ref, gain);
+
+        self.check_reference(enable_ref)?;
+
+        self.sync_if_lazy();
+        let previous = self.control_or_default();
+        let control = ControlRegister {
+            mode,
+            reference_enabled: enable_ref,
+            gain,
+        };
+        let written = self.control != Some(control);
+        if written {
+            self.write_control(control)?;
+        }
+
+        Ok(ControlChange { previous, written })
+    }
+
+    /// Write `config`'s mode, reference, and gain to the control register and
+    /// read it back, restoring the previous control register if it doesn't match.
+    ///
+    /// The control register is written even if the driver last wrote the same
+    /// settings. If the write or the readback fails, or the readback differs,
+    /// the control register cached before the call is written back so the chip
+    /// is left as it was. If that wasn't known, or writing it back fails too,
+    /// the cached control register becomes unknown. The driver only keeps
+    /// `config` for later calls to `begin()` once it is confirmed.
+    ///
+    /// Returns `Error::VerifyMismatch` with the encoded control words if the
+    /// readback differs, or the bus error from the write or readback. Returns
+    /// `Error::InvalidConfig` or `Error::ReferenceNotAvailable` without touching
+    /// the bus if `config` can't be used.
+    pub fn apply_config_verified(&mut self, config: Config) -> Result<(), Error<I2C::Error>> {
+        if !config.is_valid() {
+            return Err(Error::InvalidConfig);
+        }
+        self.check_reference(config.reference_enabled)?;
+
+        self.sync_if_lazy();
+        let previous = self.control;
+        let control = ControlRegister {
+            mode: config.mode,
+            reference_enabled: config.reference_enabled,
+            gain: config.gain,
+        };
+
+        let result = self.write_control(control).and_then(|()| {
+            let actual = self.read_control()?;
+            if actual != control {
+                return Err(Error::VerifyMismatch {
+                    expected: encode_control(control.mode, control.reference_enabled, control.gain),
+                    actual: encode_control(actual.mode, actual.reference_enabled, actual.gain),
+                });
+            }
+
+            Ok(())
+        });
+        if let Err(error) = result {
+            self.control = None;
+            if let Some(previous) = previous {
+                // The error that stopped the change is the one reported
+                let _ = self.write_control(previous);
+            }
+            return Err(error);
+        }
+        self.set_config(config);
+
+        Ok(())
+    }
+
+    /// Set the gain, keeping the current operating mode and reference setting.
+    ///
+    /// The mode and reference are those last written by the driver, or the
+    /// power-on defaults if it hasn't configured the chip yet. Skips unchanged
+    /// writes and reports the state it replaced like `set_mode()`.
+    pub fn set_gain(&mut self, gain: Gain) -> Result<ControlChange, Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let control = self.control_or_default();
+        self.set_mode(control.mode, control.reference_enabled, gain)
+    }
+
+    /// Set the gain and rescale the output code so the output voltage stays the
+    /// same.
+    ///
+    /// Halving the code when switching to 2x rounds to the nearest code, halfway
+    /// values up. The step that lowers the output is done first, so the output
+    /// dips rather than overshoots between the two writes.
+    ///
+    /// Returns `Error::UnknownState` if the output code isn't known, and
+    /// `Error::ValueOutOfRange` without writing if the doubled code for 1x
+    /// would be above `max_code()`.
+    pub fn set_gain_preserving_voltage(&mut self, gain: Gain) -> Result<(), Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let code = self.dac_code.ok_or(Error::UnknownState)?;
+
+        match (self.control_or_default().gain, gain) {
+            (Gain::X2, Gain::X1) => {
+                let code = code
+                    .checked_mul(2)
+                    .filter(|&code| code <= self.max_code())
+                    .ok_or(Error::ValueOutOfRange)?;
+                self.set_gain(gain)?;
+                self.write_update_dac(code)
+            }
+            (Gain::X1, Gain::X2) => {
+                self.write_update_dac(code / 2 + code % 2)?;
+                self.set_gain(gain)?;
+
+                Ok(())
+            }
+            _ => {
+                self.set_gain(gain)?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Enable or disable the internal reference, keeping the current operating mode
+    /// and gain.
+    ///
+    /// The mode and gain are those last written by the driver, or the power-on
+    /// defaults if it hasn't configured the chip yet. Skips unchanged writes and
+    /// reports the state it replaced like `set_mode()`.
+    pub fn set_reference(&mut self, enabled: bool) -> Result<ControlChange, Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let control = self.control_or_default();
+        self.set_mode(control.mode, enabled, control.gain)
+    }
+
+    /// Power down the output, keeping the current reference and gain settings.
+    ///
+    /// `mode` is normally one of the 1k, 100k, or tristate output modes. The DAC
+    /// register keeps its code while powered down.
+    pub fn power_down(&mut self, mode: OperatingMode) -> Result<(), Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let control = self.control_or_default();
+        self.set_mode(mode, control.reference_enabled, control.gain)?;
+
+        Ok(())
+    }
+
+    /// Return to normal mode with the reference and gain from before `power_down()`.
+    ///
+    /// The output resumes the code held in the DAC register, so there is no need
+    /// to run `update_dac()` afterwards.
+    pub fn wake(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let control = self.control_or_default();
+        self.set_mode(
+            OperatingMode::NormalMode,
+            control.reference_enabled,
+            control.gain,
+        )?;
+
+        Ok(())
+    }
+
+    /// Switch the output to tristate, keeping the reference and gain settings.
+    pub fn into_tristate(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.power_down(OperatingMode::OutputTristate)
+    }
+
+    /// Switch the output to 1k Ohm to ground, keeping the reference and gain settings.
+    pub fn into_1k_impedance(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.power_down(OperatingMode::Output1kImpedance)
+    }
+
+    /// Switch the output to 100k Ohm to ground, keeping the reference and gain settings.
+    pub fn into_100k_impedance(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.power_down(OperatingMode::Output100kImpedance)
+    }
+
+    /// Switch back to normal mode, keeping the reference and gain settings.
+    ///
+    /// Same as `wake()`.
+    pub fn into_normal(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.wake()
+    }
+
+    /// Put everything driven by the DAC into a safe state, such as before
+    /// cutting power.
+    ///
+    /// In order:
+    ///
+    /// 1. The external output stage is shut down, if a pin is set with
+    ///    `with_shutdown_pin()`, so the load is isolated first.
+    /// 2. The output is switched to tristate.
+    /// 3. The internal reference is disabled, once nothing depends on it.
+    ///
+    /// Each step is tried even if an earlier one failed, and the first error is
+    /// returned. Unchanged control writes are skipped like `set_mode()`.
+    pub fn safe_shutdown(&mut self) -> Result<(), Error<I2C::Error>> {
+        let mut result = Ok(());
+
+        if SHDN::CONNECTED {
+            match self.shutdown_pin.set_enabled(false) {
+                Ok(()) => self.output_enabled = false,
+                Err(kind) => result = Err(Error::Pin(kind)),
+            }
+        }
+
+        let tristate = self.into_tristate();
+        let gain = self.control_or_default().gain;
+        let reference_off = self.set_mode(OperatingMode::OutputTristate, false, gain);
+
+        result.and(tristate).and(reference_off.map(|_| ()))
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Write a voltage in millivolts to the input register and update the DAC register.
+    ///
+    /// This uses integer math only. The voltage is rounded to the nearest code,
+    /// with halfway values rounded up, and voltages that round outside the code
+    /// range are handled by the overflow policy. A zero reference voltage returns
+    /// `Error::InvalidConfig`. The code is then corrected by the calibration.
+    pub fn write_millivolts(&mut self, mv: Millivolts) -> Result<(), Error<I2C::Error>> {
+        let full_scale = self.full_scale_millivolts() as u64;
+        if full_scale == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let code = millivolts_to_code(full_scale, self.code_span(), mv.0);
+        let code = self.limit_code(code as i64)?;
+
+        self.write_calibrated(code)
+    }
+
+    /// Convert a DAC code to the voltage it outputs, in millivolts.
+    ///
+    /// This uses integer math only, and rounds to the nearest millivolt.
+    pub fn code_to_millivolts(&self, code: u16) -> u32 {
+        let span = self.code_span();
+
+        ((code as u64 * self.full_scale_millivolts() as u64 + span / 2) / span) as u32
+    }
+
+    /// The voltage being output, in millivolts, from the cached state alone.
+    ///
+    /// Converts `current_code()` like `code_to_millivolts()`, using the cached
+    /// gain and the configured reference voltage. The calibration isn't taken
+    /// into account. `None` if the code or mode isn't known, or the chip isn't
+    /// in `OperatingMode::NormalMode`.
+    pub fn current_millivolts(&self) -> Option<u32> {
+        self.output_code().map(|code| self.code_to_millivolts(code))
+    }
+
+    /// Write a voltage in microvolts to the input register and update the DAC
+    /// register.
+    ///
+    /// Like `write_millivolts()`, but keeps the sub-millivolt precision of the
+    /// 16-bit parts. Uses integer math only, rounding to the nearest code with
+    /// halfway values rounded up.
+    pub fn write_microvolts(&mut self, uv: Microvolts) -> Result<(), Error<I2C::Error>> {
+        let full_scale = self.full_scale_millivolts() as u128 * 1000;
+        if full_scale == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let code = (uv.0 as u128 * self.code_span() as u128 + full_scale / 2) / full_scale;
+        let code = self.limit_code(code.min(i64::MAX as u128) as i64)?;
+
+        self.write_calibrated(code)
+    }
+
+    /// Convert a DAC code to the voltage it outputs, in microvolts.
+    ///
+    /// This uses integer math only, and rounds to the nearest microvolt.
+    pub fn code_to_microvolts(&self, code: u16) -> u64 {
+        let span = self.code_span();
+
+        (code as u64 * self.full_scale_millivolts() as u64 * 1000 + span / 2) / span
+    }
+
+    /// Write a voltage in Q16.16 fixed-point millivolts to the input register
+    /// and update the DAC register.
+    ///
+    /// `value` is the voltage in millivolts times 65536, so `0x0001_8000` is
+    /// 1.5mV. Like `write_millivolts()`, this uses integer math only and rounds
+    /// to the nearest code with halfway values rounded up. The scaling is done
+    /// in checked 64-bit math, and an overflow returns `Error::ValueOutOfRange`.
+    /// Negative voltages and voltages that round past full scale are handled by
+    /// the overflow policy.
+    pub fn write_q16_16_millivolts(&mut self, value: i32) -> Result<(), Error<I2C::Error>> {
+        let full_scale = (self.full_scale_millivolts() as i64) << 16;
+        if full_scale == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let code = (value as i64)
+            .checked_mul(self.code_span() as i64)
+            .and_then(|scaled| scaled.checked_add(full_scale / 2))
+            .ok_or(Error::ValueOutOfRange)?
+            .div_euclid(full_scale);
+        let code = self.limit_code(code)?;
+
+        self.write_calibrated(code)
+    }
+
+    /// Convert a DAC code to the voltage it outputs, in Q16.16 fixed-point
+    /// millivolts.
+    ///
+    /// This uses integer math only, and rounds to the nearest 1/65536mV.
+    /// Saturates at `i32::MAX`, just under 32.768V.
+    pub fn code_to_q16_16_millivolts(&self, code: u16) -> i32 {
+        let span = self.code_span() as u128;
+        let scaled = ((code as u128 * self.full_scale_millivolts() as u128) << 16) + span / 2;
+
+        (scaled / span).min(i32::MAX as u128) as i32
+    }
+
+    /// Difference between a voltage and the nearest voltage the DAC can output,
+    /// in millivolts.
+    ///
+    /// This uses integer math only and rounds to the nearest millivolt. Voltages
+    /// past full scale are compared with the largest code's voltage.
+    pub fn quantization_error_mv(&self, target_mv: u32) -> u32 {
+        let full_scale = self.full_scale_millivolts() as u64;
+        if full_scale == 0 {
+            return target_mv;
+        }
+
+        let span = self.code_span();
+        let target = target_mv as u64 * span;
+        let code = ((target + full_scale / 2) / full_scale).min(self.max_code() as u64);
+
+        ((target.abs_diff(code * full_scale) + span / 2) / span) as u32
+    }
+
+    /// Voltage of one code step, `vref * gain / (max_code() + 1)`, in microvolts.
+    ///
+    /// Rounded to the nearest microvolt.
+    pub fn lsb_microvolts(&self) -> u32 {
+        let span = self.code_span();
+
+        ((self.full_scale_millivolts() as u64 * 1000 + span / 2) / span) as u32
+    }
+
+    /// Full-scale voltage, `vref * gain`, in millivolts.
+    ///
+    /// The DAC's largest output is one code step below this. Saturates at `u32::MAX`.
+    pub fn full_scale_millivolts(&self) -> u32 {
+        let gain = match self.control_or_default().gain {
+            Gain::X1 => 1,
+            Gain::X2 => 2,
+        };
+
+        self.config.vref_millivolts.saturating_mul(gain)
+    }
+
+    /// Number of codes, `max_code() + 1`, which full scale is divided into.
+    fn code_span(&self) -> u64 {
+        self.max_code() as u64 + 1
+    }
+}
+
+#[cfg(feature = "float")]
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Write a voltage to the input register and update the DAC register.
+    ///
+    /// The voltage is rounded to the nearest code like `voltage_to_code()`, but
+    /// voltages that round outside the code range are handled by the overflow
+    /// policy. NaN returns `Error::ValueOutOfRange`, and a zero reference voltage
+    /// returns `Error::InvalidConfig`. The code is then corrected by the calibration.
+    pub fn write_voltage(&mut self, volts: Volts) -> Result<(), Error<I2C::Error>> {
+        if self.config.vref_millivolts == 0 {
+            return Err(Error::InvalidConfig);
+        }
+
+        let code = round_nearest(volts.0 / self.lsb_voltage());
+        if code.is_nan() {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        // Float to int casts saturate, leaving the overflow policy to handle it
+        let code = self.limit_code(code as i64)?;
+
+        self.write_calibrated(code)
+    }
+
+    /// Output a voltage, running `begin()` first if the driver hasn't yet.
+    ///
+    /// A quick way to get a voltage out during bring-up. The first call resets
+    /// and configures the chip from the driver's `Config`, and later calls only
+    /// write the voltage like `write_voltage()`. A failed `begin()` is tried
+    /// again on the next call.
+    pub fn output_voltage_oneshot(&mut self, volts: Volts) -> Result<(), Error<I2C::Error>> {
+        if !self.initialized {
+            self.begin()?;
+        }
+
+        self.write_voltage(volts)
+    }
+
+    /// Write a percentage of full scale and update the DAC register.
+    ///
+    /// `100.0` is full scale and `50.0` is mid-scale for any resolution. The code is
+    /// `percent / 100 * max_code()` rounded to the nearest code, with halfway values
+    /// rounded up. Values outside `0.0..=100.0` clamp, and NaN writes zero-scale.
+    pub fn write_percent(&mut self, percent: f32) -> Result<(), Error<I2C::Error>> {
+        let max_code = self.max_code();
+        let code = round_nearest(percent.clamp(0.0, 100.0) / 100.0 * max_code as f32);
+
+        // Float to int casts saturate, and NaN becomes zero
+        self.write_update_dac((code as u16).min(max_code))
+    }
+
+    /// Convert a voltage to the DAC code that outputs it.
+    ///
+    /// The full-scale voltage is `vref * gain`. Results are rounded to the
+    /// nearest code, with halfway values rounded up. Negative voltages clamp
+    /// to zero and voltages past full scale clamp to the maximum code.
+    pub fn voltage_to_code(&self, volts: f32) -> u16 {
+        self.voltage_to_code_rounded(volts, RoundMode::Nearest)
+    }
+
+    /// Convert a voltage to a DAC code, rounding as `mode` says.
+    ///
+    /// `RoundMode::Nearest` breaks ties by rounding up, like `voltage_to_code()`.
+    /// The result is clamped to the code range, and NaN converts to zero.
+    pub fn voltage_to_code_rounded(&self, volts: f32, mode: RoundMode) -> u16 {
+        let code = round_code(volts / self.lsb_voltage(), mode);
+
+        code.min(self.max_code() as u32) as u16
+    }
+
+    /// Convert a voltage to the DAC code that outputs it, rejecting voltages the
+    /// DAC can't output.
+    ///
+    /// Rounds like `voltage_to_code()`, but never clamps: NaN, negative voltages,
+    /// and voltages that round above `max_code()` return `Error::ValueOutOfRange`
+    /// whatever the overflow policy, and a zero reference voltage returns
+    /// `Error::InvalidConfig`. This makes no bus access and can't panic, so it
+    /// suits builds that deny panics.
+    pub fn try_voltage_to_code(&self, volts: f32) -> Result<u16, Error<I2C::Error>> {
+        if self.full_scale_millivolts() == 0 {
+            return Err(Error::InvalidConfig);
+        }
+        if volts.is_nan() || volts < 0.0 {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        let code = round_code(volts / self.lsb_voltage(), RoundMode::Nearest);
+        if code > self.max_code() as u32 {
+            return Err(Error::ValueOutOfRange);
+        }
+
+        Ok(code as u16)
+    }
+
+    /// The codes either side of a voltage, rounded down and up.
+    ///
+    /// Both codes are the same when the voltage is exactly one code's voltage.
+    /// Results are clamped to the code range like `voltage_to_code_rounded()`.
+    pub fn bracketing_codes(&self, volts: f32) -> (u16, u16) {
+        (
+            self.voltage_to_code_rounded(volts, RoundMode::Floor),
+            self.voltage_to_code_rounded(volts, RoundMode::Ceil),
+        )
+    }
+
+    /// Whether a voltage is exactly the output of some code.
+    ///
+    /// Voltages outside the output range are never representable.
+    pub fn is_representable_exactly(&self, volts: f32) -> bool {
+        let exact = volts / self.lsb_voltage();
+
+        (0.0..=self.max_code() as f32).contains(&exact) && exact as u16 as f32 == exact
+    }
+
+    /// Convert a DAC code to the voltage it outputs.
+    pub fn code_to_voltage(&self, code: u16) -> f32 {
+        code as f32 * self.lsb_voltage()
+    }
+
+    /// The voltage being output, from the cached state alone.
+    ///
+    /// Like `current_millivolts()`, but converted like `code_to_voltage()`.
+    pub fn current_voltage(&self) -> Option<f32> {
+        self.output_code().map(|code| self.code_to_voltage(code))
+    }
+
+    /// Full-scale voltage, `vref * gain`.
+    ///
+    /// The DAC's largest output is one code step below this.
+    pub fn full_scale_voltage(&self) -> f32 {
+        self.full_scale_millivolts() as f32 / 1000.0
+    }
+
+    /// Voltage of one code step, `vref * gain / (max_code() + 1)`.
+    pub fn lsb_voltage(&self) -> f32 {
+        self.full_scale_voltage() / self.code_span() as f32
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Limit a DAC code by the overflow policy and write it left-justified in the
+    /// 16-bit data word.
+    ///
+    /// Returns the code that was written.
+    fn write_code(&mut self, command: Command, value: u16) -> Result<u16, Error<I2C::Error>> {
+        let code = self.limit_code(value as i64)?;
+        self.write(command, self.model.resolution().justify(code))?;
+
+        Ok(code)
+    }
+
+    /// Apply the overflow policy to a code that may be outside `0..=max_code()`.
+    fn limit_code(&self, code: i64) -> Result<u16, Error<I2C::Error>> {
+        self.overflow_policy
+            .limit(code, self.max_code())
+            .ok_or(Error::ValueOutOfRange)
+    }
+
+    /// Check a new output code against the output mode, the monotonic guard and
+    /// the slew limit.
+    pub(crate) fn check_output_change(&self, code: u16) -> Result<(), Error<I2C::Error>> {
+        self.check_output_change_from(self.dac_code, code)
+    }
+
+    /// Check a move from `previous` to `code` against the `begin_monotonic()`
+    /// guard and the `set_max_slew()` limit.
+    pub(crate) fn check_output_change_from(
+        &self,
+        previous: Option<u16>,
+        code: u16,
+    ) -> Result<(), Error<I2C::Error>> {
+        let powered_down = self
+            .control
+            .is_some_and(|control| control.mode != OperatingMode::NormalMode);
+        if powered_down && !self.allow_powered_down_writes {
+            return Err(Error::NotInOutputMode);
+        }
+
+        if let Some(max_step) = self.max_slew_code_per_ms {
+            let previous = previous.ok_or(Error::UnknownState)?;
+            if previous.abs_diff(code) as u32 > max_step {
+                return Err(Error::SlewLimitExceeded {
+                    previous,
+                    requested: code,
+                });
+            }
+        }
+
+        let (Some(direction), Some(previous)) = (self.monotonic, previous) else {
+            return Ok(());
+        };
+
+        let allowed = match direction {
+            Direction::Rising => code >= previous,
+            Direction::Falling => code <= previous,
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::MonotonicityViolation {
+                previous,
+                requested: code,
+            })
+        }
+    }
+
+    /// Read a register back with `read` and check it holds `expected`.
+    fn check_readback(
+        &mut self,
+        expected: u16,
+        read: fn(&mut Self) -> Result<u16, Error<I2C::Error>>,
+    ) -> Result<(), Error<I2C::Error>> {
+        let actual = read(self)?;
+        if actual != expected {
+            return Err(Error::VerifyMismatch { expected, actual });
+        }
+
+        Ok(())
+    }
+
+    /// Keep `config` for `begin()`, and take the calibration from it if it is
+    /// valid.
+    fn set_config(&mut self, config: Config) {
+        self.config = config;
+        if config.is_valid() {
+            self.calibration = config.calibration;
+        }
+    }
+
+    /// Reset and configure the chip from the driver's `Config`, preloading
+    /// `output` before the control write and updating to it after.
+    fn begin_with(&mut self, output: Option<u16>) -> Result<(), Error<I2C::Error>> {
+        self.check_reference(self.config.reference_enabled)?;
+        self.reset()?;
+        if let Some(code) = output {
+            self.write_dac(code)?;
+        }
+        self.write_control(ControlRegister {
+            mode: self.config.mode,
+            reference_enabled: self.config.reference_enabled,
+            gain: self.config.gain,
+        })?;
+        if output.is_some() {
+            self.check_update()?;
+            self.update_from_input()?;
+        }
+        self.initialized = true;
+
+        Ok(())
+    }
+
+    /// Write a code to the input and DAC registers, then the control register,
+    /// skipping either if `None`.
+    ///
+    /// The code write skips the output checks, as it restores rather than changes
+    /// the output.
+    fn rewrite_state(
+        &mut self,
+        code: Option<u16>,
+        control: Option<ControlRegister>,
+    ) -> Result<(), Error<I2C::Error>> {
+        if let Some(control) = control {
+            self.check_reference(control.reference_enabled)?;
+        }
+
+        if let Some(code) = code {
+            self.write_code(Command::WriteDACAndInput, code)?;
+            self.input_code = Some(code);
+            self.dac_code = Some(code);
+            self.input_pending = false;
+        }
+        if let Some(control) = control {
+            self.write_control(control)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether an update now would output a new code, warning if not.
+    pub(crate) fn update_kind(&self) -> UpdateKind {
+        if self.input_pending {
+            return UpdateKind::Fresh;
+        }
+        warn!("update with no write_dac() since the last update");
+
+        UpdateKind::Stale
+    }
+
+    /// Check and gate moving the input register's code to the output, as
+    /// `update_dac()` does.
+    pub(crate) fn check_update(&mut self) -> Result<(), Error<I2C::Error>> {
+        let code = self.input_code.ok_or(Error::UnknownState)?;
+        self.check_output_change(code)?;
+
+        self.gate_output()
+    }
+
+    /// Move the input register to the output with the UPDATE_DAC command,
+    /// without the checks made by `update_dac()`.
+    fn update_from_input(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write(Command::UpdateDAC, 0x00)?;
+        self.dac_code = self.input_code;
+        self.input_pending = false;
+
+        Ok(())
+    }
+
+    /// Read back the input register after a failed code write, for
+    /// `Error::WriteInterrupted`.
+    ///
+    /// The DAC register may or may not have been updated, so its cached code
+    /// becomes unknown.
+    fn recover_interrupted(&mut self, error: I2C::Error) -> Error<I2C::Error> {
+        let readback = self.read_input().ok();
+        self.input_code = readback;
+        self.dac_code = None;
+        self.input_pending = false;
+
+        Error::WriteInterrupted { error, readback }
+    }
+
+    /// Write the control register and cache it, even if it is unchanged.
+    fn write_control(&mut self, control: ControlRegister) -> Result<(), Error<I2C::Error>> {
+        self.write(
+            Command::WriteControl,
+            encode_control(control.mode, control.reference_enabled, control.gain),
+        )?;
+        self.control = Some(control);
+
+        Ok(())
+    }
+
+    /// Check the model has an internal reference if it is to be enabled.
+    fn check_reference(&self, enabled: bool) -> Result<(), Error<I2C::Error>> {
+        if enabled && !self.model.has_internal_reference() {
+            return Err(Error::ReferenceNotAvailable);
+        }
+
+        Ok(())
+    }
+
+    /// Run the one-time readback armed by `lazy_sync()`, if the cached state
+    /// isn't already known. A failed readback leaves the state unknown.
+    pub(crate) fn sync_if_lazy(&mut self) {
+        if !self.lazy_sync {
+            return;
+        }
+        self.lazy_sync = false;
+
+        if self.control.is_none() || self.input_code.is_none() || self.dac_code.is_none() {
+            let _ = self.restore_from_device();
+        }
+    }
+
+    /// The cached DAC code, if it is known to be driving the output.
+    fn output_code(&self) -> Option<u16> {
+        let control = self.control?;
+
+        (control.mode == OperatingMode::NormalMode)
+            .then_some(self.dac_code)
+            .flatten()
+    }
+
+    /// Cached control register, or its power-on state if it isn't known.
+    fn control_or_default(&self) -> ControlRegister {
+        self.control.unwrap_or(power_on_control(self.model))
+    }
+
+    /// Set the cached chip state to what it is after a reset.
+    fn reset_state(&mut self) {
+        self.control = Some(power_on_control(self.model));
+        self.input_code = Some(POR_CODE);
+        self.dac_code = Some(POR_CODE);
+        self.input_pending = false;
+    }
+
+    /// Read a register and right-justify the DAC code in it.
+    fn read_code(&mut self, command: Command) -> Result<u16, Error<I2C::Error>> {
+        Ok(self.model.resolution().unjustify(self.read(command)?))
+    }
+
+    /// Write a command, retrying transient errors as set by `with_retries()`.
+    fn write(&mut self, command: Command, data: u16) -> Result<(), Error<I2C::Error>> {
+        let mut retries = self.retries;
+        let mut retrying = None;
+        loop {
+            let result = self.write_once(command, data);
+            match &result {
+                Ok(()) => self.stats.record_write(command),
+                Err(Error::I2c(error)) => self.stats.record_error(error.kind()),
+                Err(_) => {}
+            }
+
+            // A refused retry reports the bus error it was retrying
+            let result = match (result, retrying.take()) {
+                (Err(Error::RateLimited { .. }), Some(error)) => Err(Error::I2c(error)),
+                (result, _) => result,
+            };
+            match result {
+                Err(Error::I2c(error)) if retries > 0 && is_transient(&error) => {
+                    trace!("retry write command {=u8:#04x}", command.bits());
+                    retries -= 1;
+                    retrying = Some(error);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn write_once(&mut self, command: Command, data: u16) -> Result<(), Error<I2C::Error>> {
+        let frame = frame(command, data);
+        trace!("write command {=u8:#04x} data {=u16:#06x}", frame[0], data);
+
+        self.throttle()?;
+        let result = self.i2c.write(self.addr, &frame);
+        self.mark_write();
+        if let Some(observer) = self.observer {
+            observer(
+                frame[0],
+                data,
+                result.as_ref().copied().map_err(|error| error.kind()),
+            );
+        }
+
+        result.map_err(Error::I2c)?;
+        match command {
+            Command::WriteControl => self.last_control_word = Some(data),
+            Command::WriteInput | Command::WriteDACAndInput => self.last_dac_word = Some(data),
+            Command::NOP | Command::UpdateDAC => {}
+        }
+
+        Ok(())
+    }
+
+    /// Read the 16-bit register selected by the command byte.
+    ///
+    /// The command byte of the register's write command is used as the read pointer.
+    fn read(&mut self, command: Command) -> Result<u16, Error<I2C::Error>> {
+        let mut buffer = [0u8; 2];
+
+        self.i2c
+            .write_read(self.addr, &[command.bits()], &mut buffer)
+            .map_err(Error::I2c)?;
+
+        Ok(u16::from_be_bytes(buffer))
     }
 }
@@ -0,0 +1,228 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{Error as _, ErrorKind, OutputPin};
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, DisabledOutputPolicy, Error, UpdateKind};
+
+/// Time /RESET is held low, comfortably above the datasheet minimum pulse width.
+const RESET_PULSE_US: u32 = 1;
+
+/// Time allowed after /RESET is released before the chip is used.
+const RESET_RECOVERY_US: u32 = 100;
+
+/// Placeholder for a control pin that isn't connected
+pub struct NoPin;
+
+/// The enable line of an external output stage, set with `with_shutdown_pin()`
+///
+/// Implemented for any `OutputPin`, and for `NoPin`, where there is no line and
+/// driving it does nothing.
+pub trait ShutdownControl {
+    /// Whether there is a line to drive.
+    const CONNECTED: bool = true;
+
+    /// Drive the line high to enable the stage, or low to shut it down.
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), ErrorKind>;
+}
+
+impl ShutdownControl for NoPin {
+    const CONNECTED: bool = false;
+
+    fn set_enabled(&mut self, _enabled: bool) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+impl<P: OutputPin> ShutdownControl for P {
+    fn set_enabled(&mut self, enabled: bool) -> Result<(), ErrorKind> {
+        self.set_state(enabled.into()).map_err(|error| error.kind())
+    }
+}
+
+impl<I2C: I2c, LDAC: OutputPin> AdafruitAD569x<I2C, LDAC> {
+    /// Create a driver for an AD5693R with its /LDAC pin on a GPIO.
+    ///
+    /// The pin should be driven high before the driver is used.
+    pub fn new_with_ldac(i2c: I2C, addr: u8, ldac: LDAC) -> Self {
+        AdafruitAD569x::new(i2c, addr).with_ldac_pin(ldac)
+    }
+}
+
+impl<I2C: I2c, LDAC: OutputPin, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Update the DAC register from the input register by pulsing /LDAC.
+    ///
+    /// The pin is driven low then high, which has the same effect as `update_dac()`
+    /// without a bus transaction, and returns the same `UpdateKind`.
+    pub fn update_via_ldac(&mut self) -> Result<UpdateKind, Error<I2C::Error>> {
+        let kind = self.update_kind();
+        self.check_update()?;
+        self.ldac
+            .set_low()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        self.ldac
+            .set_high()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        self.dac_code = self.input_code;
+        self.input_pending = false;
+
+        Ok(kind)
+    }
+}
+
+impl<I2C: I2c, LDAC, RST: OutputPin, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Reset the chip by pulsing /RESET.
+    ///
+    /// The pin is driven low for the minimum pulse width, then high, followed by
+    /// a delay for the chip to come back up. Like `reset()`, this resets the DAC to
+    /// zero-scale and the registers to their defaults, and the driver's cached
+    /// state is reset to match.
+    pub fn hard_reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<I2C::Error>> {
+        self.reset_pin
+            .set_low()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        delay.delay_us(RESET_PULSE_US);
+        self.reset_pin
+            .set_high()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        delay.delay_us(RESET_RECOVERY_US);
+        self.reset_state();
+
+        Ok(())
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: OutputPin> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Enable the external output stage by driving the shutdown line high.
+    pub fn enable_output(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.shutdown_pin
+            .set_high()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        self.output_enabled = true;
+
+        Ok(())
+    }
+
+    /// Shut down the external output stage by driving the shutdown line low.
+    ///
+    /// The chip keeps driving its output. What the writes do until
+    /// `enable_output()` is set by `set_disabled_output_policy()`.
+    pub fn disable_output(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.shutdown_pin
+            .set_low()
+            .map_err(|error| Error::Pin(error.kind()))?;
+        self.output_enabled = false;
+
+        Ok(())
+    }
+}
+
+impl<I2C: I2c, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, NoPin, RST, SHDN> {
+    /// Use a GPIO connected to the /LDAC pin for `update_via_ldac()`.
+    ///
+    /// The pin should be driven high before the driver is used.
+    pub fn with_ldac_pin<LDAC: OutputPin>(
+        self,
+        ldac: LDAC,
+    ) -> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+        self.replace_pins(|_, reset_pin, shutdown| (ldac, reset_pin, shutdown))
+    }
+}
+
+impl<I2C: I2c, LDAC, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, NoPin, SHDN> {
+    /// Use a GPIO connected to the /RESET pin for `hard_reset()`.
+    ///
+    /// The pin should be driven high before the driver is used.
+    pub fn with_reset_pin<RST: OutputPin>(
+        self,
+        reset_pin: RST,
+    ) -> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+        self.replace_pins(|ldac, _, shutdown| (ldac, reset_pin, shutdown))
+    }
+}
+
+impl<I2C: I2c, LDAC, RST> AdafruitAD569x<I2C, LDAC, RST, NoPin> {
+    /// Use a GPIO driving the enable line of an external output stage, for
+    /// `enable_output()` and `disable_output()`.
+    ///
+    /// The line is high when the stage is enabled. It should be driven high
+    /// before the driver is used.
+    pub fn with_shutdown_pin<SHDN: OutputPin>(
+        self,
+        shutdown_pin: SHDN,
+    ) -> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+        self.replace_pins(|ldac, reset_pin, _| (ldac, reset_pin, shutdown_pin))
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Set what `write_update_dac()`, `update_dac()`, and `update_via_ldac()` do
+    /// while `disable_output()` has shut down the external output stage.
+    ///
+    /// Defaults to `DisabledOutputPolicy::Allow`. The policy only matters once
+    /// a pin is set with `with_shutdown_pin()`.
+    pub fn set_disabled_output_policy(&mut self, policy: DisabledOutputPolicy) {
+        self.disabled_output_policy = policy;
+    }
+
+    /// Apply the `set_disabled_output_policy()` policy before the output changes.
+    pub(crate) fn gate_output(&mut self) -> Result<(), Error<I2C::Error>> {
+        if self.output_enabled {
+            return Ok(());
+        }
+
+        match self.disabled_output_policy {
+            DisabledOutputPolicy::Refuse => Err(Error::OutputDisabled),
+            DisabledOutputPolicy::AutoEnable => {
+                self.shutdown_pin.set_enabled(true).map_err(Error::Pin)?;
+                self.output_enabled = true;
+
+                Ok(())
+            }
+            DisabledOutputPolicy::Allow => Ok(()),
+        }
+    }
+
+    /// Swap out the control pins, keeping the rest of the driver state.
+    fn replace_pins<L, R, S>(
+        self,
+        pins: impl FnOnce(LDAC, RST, SHDN) -> (L, R, S),
+    ) -> AdafruitAD569x<I2C, L, R, S> {
+        let (ldac, reset_pin, shutdown_pin) = pins(self.ldac, self.reset_pin, self.shutdown_pin);
+
+        AdafruitAD569x {
+            i2c: self.i2c,
+            ldac,
+            reset_pin,
+            shutdown_pin,
+            output_enabled: self.output_enabled,
+            disabled_output_policy: self.disabled_output_policy,
+            addr: self.addr,
+            model: self.model,
+            config: self.config,
+            control: self.control,
+            input_code: self.input_code,
+            dac_code: self.dac_code,
+            input_pending: self.input_pending,
+            calibration: self.calibration,
+            overflow_policy: self.overflow_policy,
+            retries: self.retries,
+            monotonic: self.monotonic,
+            output_stage: self.output_stage,
+            transconductance_microamps_per_volt: self.transconductance_microamps_per_volt,
+            initialized: self.initialized,
+            observer: self.observer,
+            last_control_word: self.last_control_word,
+            last_dac_word: self.last_dac_word,
+            verify_writes: self.verify_writes,
+            lazy_sync: self.lazy_sync,
+            allow_powered_down_writes: self.allow_powered_down_writes,
+            drift_table: self.drift_table,
+            accumulated: self.accumulated,
+            max_slew_code_per_ms: self.max_slew_code_per_ms,
+            rate_limit: self.rate_limit,
+            presets: self.presets,
+            last_write_us: self.last_write_us,
+            stats: self.stats,
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, ShutdownControl};
+
+/// Number of presets a driver holds for `set_preset()`.
+pub const PRESET_COUNT: usize = 8;
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Store `code` as preset `index`, for `recall_preset()`.
+    ///
+    /// Nothing is written. The code is checked against the overflow policy when
+    /// it is recalled, not when it is stored.
+    ///
+    /// Returns `Error::InvalidPreset` if `index` isn't below `PRESET_COUNT`.
+    pub fn set_preset(&mut self, index: usize, code: u16) -> Result<(), Error<I2C::Error>> {
+        *self.presets.get_mut(index).ok_or(Error::InvalidPreset)? = Some(code);
+
+        Ok(())
+    }
+
+    /// Forget preset `index`.
+    ///
+    /// Returns `Error::InvalidPreset` if `index` isn't below `PRESET_COUNT`.
+    pub fn clear_preset(&mut self, index: usize) -> Result<(), Error<I2C::Error>> {
+        *self.presets.get_mut(index).ok_or(Error::InvalidPreset)? = None;
+
+        Ok(())
+    }
+
+    /// The code stored as preset `index`, or `None` if it isn't set or `index`
+    /// isn't below `PRESET_COUNT`.
+    pub fn preset(&self, index: usize) -> Option<u16> {
+        self.presets.get(index).copied().flatten()
+    }
+
+    /// Write and update the DAC to the code stored as preset `index`.
+    ///
+    /// The code is written with `write_update_dac()`.
+    ///
+    /// Returns `Error::InvalidPreset` if `index` isn't below `PRESET_COUNT`, or
+    /// `Error::PresetNotSet` if nothing is stored there.
+    pub fn recall_preset(&mut self, index: usize) -> Result<(), Error<I2C::Error>> {
+        let code = self
+            .presets
+            .get(index)
+            .ok_or(Error::InvalidPreset)?
+            .ok_or(Error::PresetNotSet)?;
+
+        self.write_update_dac(code)
+    }
+}
@@ -0,0 +1,151 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, ShutdownControl};
+
+/// Function returning a free-running timestamp in microseconds
+///
+/// The timestamp may wrap on overflow, as long as it doesn't wrap twice between
+/// writes.
+pub type Clock = fn() -> u32;
+
+/// Function waiting for a number of microseconds
+pub type DelayUs = fn(u32);
+
+/// What a write does when it comes sooner than a `RateLimit` without a delay
+/// allows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RateLimitPolicy {
+    /// Write anyway. The write is still counted in `DriverStats::rate_limited`.
+    #[default]
+    Observe,
+    /// Return `Error::RateLimited` without writing.
+    ///
+    /// Each write is checked on its own, so a method that makes several writes,
+    /// such as `begin()`, can be refused partway through. Use `with_delay()` to
+    /// limit those instead. A retry from `with_retries()` is never refused, and
+    /// its bus error is returned instead.
+    Refuse,
+}
+
+/// A minimum interval between bus writes, for `set_rate_limit()`
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    min_interval_us: u32,
+    clock: Clock,
+    policy: RateLimitPolicy,
+    delay: Option<DelayUs>,
+}
+
+impl RateLimit {
+    /// Allow at most one write every `min_interval_us`, timed by `clock`.
+    ///
+    /// Defaults to `RateLimitPolicy::Observe`, with no delay.
+    pub fn new(min_interval_us: u32, clock: Clock) -> Self {
+        Self {
+            min_interval_us,
+            clock,
+            policy: RateLimitPolicy::default(),
+            delay: None,
+        }
+    }
+
+    /// Set what a write does when it comes too soon and there is no delay.
+    pub fn with_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Wait with `delay` before a write that comes too soon, instead of applying
+    /// the policy.
+    ///
+    /// Every bus write waits, including those in the middle of a method and
+    /// retries, so methods that make several writes slow down rather than fail.
+    pub fn with_delay(mut self, delay: DelayUs) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// The minimum time between writes, in microseconds.
+    pub fn min_interval_us(&self) -> u32 {
+        self.min_interval_us
+    }
+
+    /// What a write does when it comes too soon and there is no delay.
+    pub fn policy(&self) -> RateLimitPolicy {
+        self.policy
+    }
+
+    /// The delay early writes wait with, if any.
+    pub fn delay(&self) -> Option<DelayUs> {
+        self.delay
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Limit how often the driver writes to the bus, or `None` to stop.
+    ///
+    /// Every bus write is timed against the one before, including retries and
+    /// each batch of `write_update_many()`. Reads aren't limited. The first write
+    /// after setting a limit is never held back.
+    ///
+    /// An early write waits with the delay from `RateLimit::with_delay()`, or
+    /// else follows the `RateLimitPolicy`. The methods that take a `DelayNs`,
+    /// such as `ramp_to()` and `play_samples()`, also wait with `pace()` before
+    /// each write. The tristate write of a `TristateOnDrop` guard is never held
+    /// back, as it makes the output safe.
+    pub fn set_rate_limit(&mut self, rate_limit: Option<RateLimit>) {
+        self.rate_limit = rate_limit;
+        self.last_write_us = None;
+    }
+
+    /// Time until the rate limit allows the next write, in microseconds.
+    ///
+    /// Zero if a write can be made now, or if there is no rate limit.
+    pub fn write_wait_us(&self) -> u32 {
+        match (self.rate_limit, self.last_write_us) {
+            (Some(limit), Some(last)) => {
+                let elapsed = (limit.clock)().wrapping_sub(last);
+                limit.min_interval_us.saturating_sub(elapsed)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Wait with `delay` until the rate limit allows the next write.
+    pub fn pace<D: DelayNs>(&mut self, delay: &mut D) {
+        let wait_us = self.write_wait_us();
+        if wait_us > 0 {
+            delay.delay_us(wait_us);
+        }
+    }
+
+    /// Apply the rate limit before a bus write.
+    pub(crate) fn throttle(&mut self) -> Result<(), Error<I2C::Error>> {
+        let Some(limit) = self.rate_limit else {
+            return Ok(());
+        };
+        let wait_us = self.write_wait_us();
+        if wait_us == 0 {
+            return Ok(());
+        }
+        self.stats.rate_limited = self.stats.rate_limited.wrapping_add(1);
+
+        match (limit.delay, limit.policy) {
+            (Some(delay), _) => {
+                delay(wait_us);
+                Ok(())
+            }
+            (None, RateLimitPolicy::Refuse) => Err(Error::RateLimited { wait_us }),
+            (None, RateLimitPolicy::Observe) => Ok(()),
+        }
+    }
+
+    /// Note the time of a bus write, whether or not it succeeded.
+    pub(crate) fn mark_write(&mut self) {
+        if let Some(limit) = self.rate_limit {
+            self.last_write_us = Some((limit.clock)());
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use embedded_hal::digital::OutputPin;
+use embedded_hal::i2c::{I2c, Operation};
+
+use crate::{
+    AdafruitAD569x, Command, Error, Gain, Interrupted, OperatingMode, ShutdownControl, frame,
+};
+
+/// Most codes sent in one transaction by `write_update_many()`.
+const BATCH_LEN: usize = 8;
+
+/// One step of a `transition()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Step {
+    /// Write a code to the input register, as `write_dac()`.
+    WriteInput(u16),
+    /// Update the DAC register from the input register, as `update_dac()`.
+    Update,
+    /// Write a code to the input and DAC registers, as `write_update_dac()`.
+    WriteUpdate(u16),
+    /// Write the control register, as `set_mode()`.
+    SetMode {
+        /// Operating mode of the output.
+        mode: OperatingMode,
+        /// Whether the internal reference is enabled.
+        reference: bool,
+        /// Output gain.
+        gain: Gain,
+    },
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Run a sequence of writes in order.
+    ///
+    /// Each step behaves like the method it names and updates the cached state the
+    /// same way. For example, `[WriteInput(code), SetMode { .. }, Update]` preloads
+    /// a code, changes mode, and only then moves the output to the new code.
+    ///
+    /// Stops at the first error, reporting how many steps completed before it.
+    pub fn transition(&mut self, steps: &[Step]) -> Result<(), Interrupted<I2C::Error>> {
+        for (completed, &step) in steps.iter().enumerate() {
+            let result = match step {
+                Step::WriteInput(code) => self.write_dac(code),
+                Step::Update => self.update_dac().map(|_| ()),
+                Step::WriteUpdate(code) => self.write_update_dac(code),
+                Step::SetMode {
+                    mode,
+                    reference,
+                    gain,
+                } => self.set_mode(mode, reference, gain).map(|_| ()),
+            };
+            result.map_err(|error| Interrupted { completed, error })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Write and update a run of codes with as few bus transactions as possible.
+    ///
+    /// Codes are sent up to eight at a time in one `I2c::transaction()` of
+    /// write operations, each a three-byte WRITE_DAC_AND_INPUT frame. HALs merge
+    /// adjacent writes, so a batch goes out as one I2C write with the frames
+    /// back to back. Retries from `with_retries()` and the observer don't apply,
+    /// but each batch counts as one write for the rate limit. With
+    /// `verify_every_write()`, only the last code is read back, from the DAC
+    /// register, once every batch is sent.
+    ///
+    /// Every code is checked against the overflow policy, the monotonic guard,
+    /// and the slew limit, and the disabled output policy is applied, before
+    /// anything is written. On a bus error, `completed` counts the codes in the
+    /// batches sent before the failed one, and the cached output code becomes
+    /// unknown.
+    pub fn write_update_many(&mut self, codes: &[u16]) -> Result<(), Interrupted<I2C::Error>> {
+        let interrupted = |completed, error| Interrupted { completed, error };
+
+        let mut previous = self.dac_code;
+        for &code in codes {
+            let code = self
+                .limit_code(code as i64)
+                .and_then(|code| self.check_output_change_from(previous, code).map(|_| code))
+                .map_err(|error| interrupted(0, error))?;
+            previous = Some(code);
+        }
+        self.gate_output().map_err(|error| interrupted(0, error))?;
+
+        let resolution = self.model.resolution();
+        for (batch, chunk) in codes.chunks(BATCH_LEN).enumerate() {
+            let mut frames = [[0; 3]; BATCH_LEN];
+            for (frame_bytes, &code) in frames.iter_mut().zip(chunk) {
+                // Already checked, so only a clamp is left to apply
+                let code = code.min(resolution.max_code());
+                *frame_bytes = frame(Command::WriteDACAndInput, resolution.justify(code));
+            }
+
+            let mut operations = frames.each_ref().map(|bytes| Operation::Write(bytes));
+            trace!("write batch of {=usize}", chunk.len());
+            self.throttle()
+                .map_err(|error| interrupted(batch * BATCH_LEN, error))?;
+            let result = self
+                .i2c
+                .transaction(self.addr, &mut operations[..chunk.len()]);
+            self.mark_write();
+            if let Err(error) = result {
+                self.stats
+                    .record_error(embedded_hal::i2c::Error::kind(&error));
+                self.dac_code = None;
+                self.input_code = None;
+                return Err(interrupted(batch * BATCH_LEN, Error::I2c(error)));
+            }
+            for _ in chunk {
+                self.stats.record_write(Command::WriteDACAndInput);
+            }
+        }
+
+        if let Some(&last) = codes.last() {
+            let last = last.min(resolution.max_code());
+            self.input_code = Some(last);
+            self.dac_code = Some(last);
+            self.input_pending = false;
+
+            if self.verify_writes {
+                self.check_readback(last, Self::read_dac)
+                    .map_err(|error| interrupted(codes.len(), error))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A code waiting in the input register, returned by `prepare()`
+///
+/// Holds the driver until the code is moved to the output or discarded, so
+/// nothing else can be written in between.
+#[must_use = "the code isn't output until `update()` is called"]
+pub struct Prepared<'a, I2C, LDAC, RST, SHDN> {
+    dac: &'a mut AdafruitAD569x<I2C, LDAC, RST, SHDN>,
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Write a code to the input register, to be output later.
+    ///
+    /// The same write as `write_dac()`, but the returned `Prepared` must be used
+    /// to update the output or discard the code.
+    pub fn prepare(
+        &mut self,
+        value: u16,
+    ) -> Result<Prepared<'_, I2C, LDAC, RST, SHDN>, Error<I2C::Error>> {
+        self.write_dac(value)?;
+
+        Ok(Prepared { dac: self })
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> Prepared<'_, I2C, LDAC, RST, SHDN> {
+    /// Move the prepared code to the output with the UPDATE_DAC command.
+    pub fn update(self) -> Result<(), Error<I2C::Error>> {
+        self.dac.update_dac().map(|_| ())
+    }
+
+    /// Leave the prepared code in the input register without outputting it.
+    ///
+    /// A later `update_dac()` would still output it, and return
+    /// `UpdateKind::Stale`.
+    pub fn discard(self) {
+        self.dac.input_pending = false;
+    }
+}
+
+impl<I2C: I2c, LDAC: OutputPin, RST, SHDN: ShutdownControl> Prepared<'_, I2C, LDAC, RST, SHDN> {
+    /// Move the prepared code to the output by pulsing /LDAC.
+    pub fn update_via_ldac(self) -> Result<(), Error<I2C::Error>> {
+        self.dac.update_via_ldac().map(|_| ())
+    }
+}
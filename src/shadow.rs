@@ -0,0 +1,138 @@
+use core::convert::Infallible;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+use crate::{
+    AdafruitAD569x, Address, Command, Model, POR_CODE, POR_CONTROL_WORD, RESET_CONTROL_WORD,
+};
+
+const WRITE_INPUT: u8 = Command::WriteInput.bits();
+const UPDATE_DAC: u8 = Command::UpdateDAC.bits();
+const WRITE_DAC_AND_INPUT: u8 = Command::WriteDACAndInput.bits();
+const WRITE_CONTROL: u8 = Command::WriteControl.bits();
+
+/// An I2C bus with an emulated AD569x on it, for running the driver without
+/// hardware
+///
+/// Writes update the emulated registers and always succeed, and reads return
+/// them, whatever the address. Data words are stored as sent, left-justified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShadowBus {
+    input: u16,
+    dac: u16,
+    control: u16,
+}
+
+impl ShadowBus {
+    /// Create a bus with the emulated chip in its power-on state.
+    pub fn new() -> Self {
+        Self {
+            input: POR_CODE,
+            dac: POR_CODE,
+            control: POR_CONTROL_WORD,
+        }
+    }
+
+    /// Data word in the emulated DAC register.
+    pub fn dac_word(&self) -> u16 {
+        self.dac
+    }
+
+    /// Data word in the emulated input register.
+    pub fn input_word(&self) -> u16 {
+        self.input
+    }
+
+    /// Word in the emulated control register.
+    pub fn control_word(&self) -> u16 {
+        self.control
+    }
+
+    fn write_frame(&mut self, bytes: &[u8]) {
+        let [command, high_byte, low_byte] = *bytes else {
+            return;
+        };
+        let data = u16::from_be_bytes([high_byte, low_byte]);
+
+        match command {
+            WRITE_INPUT => self.input = data,
+            UPDATE_DAC => self.dac = self.input,
+            WRITE_DAC_AND_INPUT => {
+                self.input = data;
+                self.dac = data;
+            }
+            WRITE_CONTROL if data & RESET_CONTROL_WORD != 0 => *self = Self::new(),
+            WRITE_CONTROL => self.control = data,
+            _ => {}
+        }
+    }
+
+    fn register(&self, pointer: u8) -> u16 {
+        match pointer {
+            WRITE_INPUT => self.input,
+            WRITE_CONTROL => self.control,
+            _ => self.dac,
+        }
+    }
+}
+
+impl Default for ShadowBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorType for ShadowBus {
+    type Error = Infallible;
+}
+
+impl I2c for ShadowBus {
+    fn transaction(
+        &mut self,
+        _address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let mut pointer = WRITE_DAC_AND_INPUT;
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    if let Some(&first) = bytes.first() {
+                        pointer = first;
+                    }
+                    self.write_frame(bytes);
+                }
+                Operation::Read(buffer) => {
+                    let word = self.register(pointer).to_be_bytes();
+                    for (byte, value) in buffer.iter_mut().zip(word) {
+                        *byte = value;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AdafruitAD569x<ShadowBus> {
+    /// Create a driver for `model` on an emulated bus, for dry runs without
+    /// hardware.
+    ///
+    /// Every write succeeds without touching a real bus, so code using the driver
+    /// can be run on a host and checked with `current_code()` and the other
+    /// getters. The emulated registers are available with `bus_mut()`.
+    ///
+    /// ```
+    /// use ad569x::{AdafruitAD569x, Model};
+    ///
+    /// let mut dac = AdafruitAD569x::shadow(Model::AD5693R);
+    /// dac.begin().unwrap();
+    /// dac.write_update_dac(0x8000).unwrap();
+    ///
+    /// assert_eq!(dac.current_code(), Some(0x8000));
+    /// assert_eq!(dac.read_dac(), Ok(0x8000));
+    /// ```
+    pub fn shadow(model: Model) -> Self {
+        Self::with_model(ShadowBus::new(), Address::default().into(), model)
+    }
+}
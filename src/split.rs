@@ -0,0 +1,131 @@
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, ControlChange, Error, Gain, OperatingMode, UpdateKind};
+
+/// The code writing half of a driver, returned by `split()`
+///
+/// Keeps the output code cache and the settings of the code writes, such as
+/// the overflow policy, retries, and slew limit. It doesn't know the control
+/// register, so writes aren't refused while the chip is powered down.
+pub struct DacWriter<I2C> {
+    dac: AdafruitAD569x<I2C>,
+}
+
+/// The control register half of a driver, returned by `split()`
+///
+/// Keeps the control register cache. It doesn't know the output code.
+pub struct DacConfig<I2C> {
+    dac: AdafruitAD569x<I2C>,
+}
+
+impl<I2C: I2c> AdafruitAD569x<I2C> {
+    /// Split the driver into a half that writes codes and a half that configures
+    /// the chip, so they can be owned by different tasks.
+    ///
+    /// The writer keeps this driver's bus, and the config half talks to the chip
+    /// through `config_i2c`, a second handle to the same bus such as one from a
+    /// bus sharing crate. Each half keeps only the cached state it uses, so
+    /// neither has to lock the other. The config half starts with the retries
+    /// and observer of this driver.
+    pub fn split(mut self, config_i2c: I2C) -> (DacWriter<I2C>, DacConfig<I2C>) {
+        let mut config = AdafruitAD569x::with_model(config_i2c, self.addr, self.model);
+        config.config = self.config;
+        config.control = self.control.take();
+        config.retries = self.retries;
+        config.observer = self.observer;
+        config.initialized = self.initialized;
+        // A readback would give the writer a control cache that goes stale
+        self.lazy_sync = false;
+
+        (DacWriter { dac: self }, DacConfig { dac: config })
+    }
+}
+
+impl<I2C: I2c> DacWriter<I2C> {
+    /// Same as `AdafruitAD569x::write_dac()`.
+    pub fn write_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        self.dac.write_dac(value)
+    }
+
+    /// Same as `AdafruitAD569x::update_dac()`.
+    pub fn update_dac(&mut self) -> Result<UpdateKind, Error<I2C::Error>> {
+        self.dac.update_dac()
+    }
+
+    /// Same as `AdafruitAD569x::write_update_dac()`.
+    pub fn write_update_dac(&mut self, value: u16) -> Result<(), Error<I2C::Error>> {
+        self.dac.write_update_dac(value)
+    }
+
+    /// Same as `AdafruitAD569x::write_update_if_changed()`.
+    pub fn write_update_if_changed(&mut self, value: u16) -> Result<bool, Error<I2C::Error>> {
+        self.dac.write_update_if_changed(value)
+    }
+
+    /// Same as `AdafruitAD569x::current_code()`.
+    pub fn current_code(&self) -> Option<u16> {
+        self.dac.current_code()
+    }
+
+    /// Same as `AdafruitAD569x::max_code()`.
+    pub fn max_code(&self) -> u16 {
+        self.dac.max_code()
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2C {
+        self.dac.release()
+    }
+}
+
+impl<I2C: I2c> DacConfig<I2C> {
+    /// Same as `AdafruitAD569x::set_mode()`.
+    pub fn set_mode(
+        &mut self,
+        mode: OperatingMode,
+        enable_ref: bool,
+        gain: Gain,
+    ) -> Result<ControlChange, Error<I2C::Error>> {
+        self.dac.set_mode(mode, enable_ref, gain)
+    }
+
+    /// Same as `AdafruitAD569x::set_gain()`.
+    pub fn set_gain(&mut self, gain: Gain) -> Result<ControlChange, Error<I2C::Error>> {
+        self.dac.set_gain(gain)
+    }
+
+    /// Same as `AdafruitAD569x::set_reference()`.
+    pub fn set_reference(&mut self, enabled: bool) -> Result<ControlChange, Error<I2C::Error>> {
+        self.dac.set_reference(enabled)
+    }
+
+    /// Same as `AdafruitAD569x::power_down()`.
+    pub fn power_down(&mut self, mode: OperatingMode) -> Result<(), Error<I2C::Error>> {
+        self.dac.power_down(mode)
+    }
+
+    /// Same as `AdafruitAD569x::wake()`.
+    pub fn wake(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.dac.wake()
+    }
+
+    /// Same as `AdafruitAD569x::current_mode()`.
+    pub fn current_mode(&self) -> Option<OperatingMode> {
+        self.dac.current_mode()
+    }
+
+    /// Same as `AdafruitAD569x::current_gain()`.
+    pub fn current_gain(&self) -> Option<Gain> {
+        self.dac.current_gain()
+    }
+
+    /// Same as `AdafruitAD569x::reference_enabled()`.
+    pub fn reference_enabled(&self) -> Option<bool> {
+        self.dac.reference_enabled()
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2C {
+        self.dac.release()
+    }
+}
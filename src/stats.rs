@@ -0,0 +1,66 @@
+use embedded_hal::i2c::{ErrorKind, I2c};
+
+use crate::{AdafruitAD569x, Command, ShutdownControl};
+
+/// Counts of the bus writes made by a driver, from `stats()`
+///
+/// Every attempt counts, so a write that succeeds on a retry adds one error and
+/// one write. The counters wrap on overflow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DriverStats {
+    /// Successful writes of any command.
+    pub writes: u32,
+    /// Successful WRITE_INPUT commands.
+    pub input_writes: u32,
+    /// Successful UPDATE_DAC commands.
+    pub updates: u32,
+    /// Successful WRITE_DAC_AND_INPUT commands.
+    pub input_and_dac_writes: u32,
+    /// Successful WRITE_CONTROL commands, including soft resets.
+    pub control_writes: u32,
+    /// Successful NOP commands.
+    pub nops: u32,
+    /// Writes that failed on the bus.
+    pub errors: u32,
+    /// The kind of the last bus error.
+    pub last_error: Option<ErrorKind>,
+    /// Writes that came sooner than the `RateLimit` allows, whether they waited,
+    /// were refused or went ahead.
+    pub rate_limited: u32,
+}
+
+impl DriverStats {
+    /// Count a successful write of `command`.
+    pub(crate) fn record_write(&mut self, command: Command) {
+        let counter = match command {
+            Command::NOP => &mut self.nops,
+            Command::WriteInput => &mut self.input_writes,
+            Command::UpdateDAC => &mut self.updates,
+            Command::WriteDACAndInput => &mut self.input_and_dac_writes,
+            Command::WriteControl => &mut self.control_writes,
+        };
+        *counter = counter.wrapping_add(1);
+        self.writes = self.writes.wrapping_add(1);
+    }
+
+    /// Count a write that failed with `kind`.
+    pub(crate) fn record_error(&mut self, kind: ErrorKind) {
+        self.errors = self.errors.wrapping_add(1);
+        self.last_error = Some(kind);
+    }
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Counts of the bus writes made since the driver was created or
+    /// `reset_stats()`.
+    ///
+    /// The NAK the chip gives to a soft reset counts as a successful write.
+    pub fn stats(&self) -> DriverStats {
+        self.stats
+    }
+
+    /// Zero the counts returned by `stats()`.
+    pub fn reset_stats(&mut self) {
+        self.stats = DriverStats::default();
+    }
+}
@@ -0,0 +1,387 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Error, Interrupted, MIN_SETTLE_US, ShutdownControl};
+
+/// Shape of the progress through a `fade_to()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EasingCurve {
+    /// Constant rate, like `ramp_to()`.
+    Linear,
+    /// Progress raised to this power, starting slowly and finishing fast. Powers
+    /// of zero and one are linear.
+    Gamma(u8),
+    /// Slow at both ends and fastest in the middle, using smoothstep.
+    EaseInOut,
+}
+
+impl<I2C: I2c, LDAC, RST, SHDN: ShutdownControl> AdafruitAD569x<I2C, LDAC, RST, SHDN> {
+    /// Ramp the output linearly from the current code to `target`.
+    ///
+    /// The ramp takes `steps` evenly spaced `write_update_dac()` steps, waiting
+    /// `step_delay_us` between them, and the last step always lands exactly on
+    /// `target`. Ramps run up or down, and a `steps` of zero is treated as one.
+    /// A `target` above `max_code()` is handled by the overflow policy.
+    ///
+    /// Returns `Error::UnknownState` if no code has been written since the driver
+    /// was created, as there is nothing to ramp from.
+    pub fn ramp_to<D: DelayNs>(
+        &mut self,
+        target: u16,
+        steps: u16,
+        step_delay_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let start = self.dac_code.ok_or(Error::UnknownState)?;
+        let target = self.limit_code(target as i64)?;
+        let steps = steps.max(1);
+
+        for step in 1..=steps {
+            self.pace(delay);
+            self.write_update_dac(ramp_code(start, target, step, steps))?;
+
+            if step != steps {
+                delay.delay_us(step_delay_us);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write and update a code, stepping to it within the `set_max_slew()` limit.
+    ///
+    /// Without a limit this is a single `write_update_dac()`. With one, the output
+    /// moves at most the limit at a time, with 1ms between steps, and the last
+    /// step lands exactly on `value`. Moves made by other calls just before this
+    /// one aren't accounted for. A `value` above `max_code()` is handled by the
+    /// overflow policy.
+    ///
+    /// Returns `Error::UnknownState` if a limit is set and the output code isn't
+    /// known.
+    pub fn write_update_dac_slewed<D: DelayNs>(
+        &mut self,
+        value: u16,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        let Some(max_step) = self.max_slew_code_per_ms else {
+            self.pace(delay);
+            return self.write_update_dac(value);
+        };
+        self.sync_if_lazy();
+        let start = self.dac_code.ok_or(Error::UnknownState)?;
+        let target = self.limit_code(value as i64)?;
+
+        let distance = start.abs_diff(target) as u32;
+        let steps = distance.div_ceil(max_step.max(1)).clamp(1, u16::MAX as u32) as u16;
+        for step in 1..=steps {
+            if step != 1 {
+                delay.delay_ms(1);
+            }
+            self.pace(delay);
+            self.write_update_dac(ramp_code(start, target, step, steps))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a code to the output and wait for it to settle.
+    ///
+    /// The code is written with `write_update_dac()`, then this blocks for
+    /// `settle_us`. `SETTLE_TIME_US` is the datasheet's typical settling time, a
+    /// starting point for lightly loaded outputs. Nothing waits if the write fails.
+    pub fn set_and_settle<D: DelayNs>(
+        &mut self,
+        code: u16,
+        settle_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.pace(delay);
+        self.write_update_dac(code)?;
+        delay.delay_us(settle_us);
+
+        Ok(())
+    }
+
+    /// Estimate how long the output takes to settle after a step between two codes.
+    ///
+    /// The step's fraction of the code range scales the model's
+    /// `full_scale_settle_us()`, rounded up, and `MIN_SETTLE_US` is added. No
+    /// step needs no time. This is a bound for lightly loaded outputs to pass to
+    /// `set_and_settle()`, not an exact figure.
+    pub fn estimated_settle_us(&self, from: u16, to: u16) -> u32 {
+        if from == to {
+            return 0;
+        }
+
+        let step = from.abs_diff(to).min(self.max_code()) as u64;
+        let full_scale = self.model.full_scale_settle_us() as u64;
+        let slewing = (full_scale * step).div_ceil(self.max_code() as u64);
+
+        MIN_SETTLE_US + slewing as u32
+    }
+
+    /// Output `active_code` for `width_us`, then return to `rest_code`.
+    ///
+    /// Both codes are written with `write_update_dac()`. The error's `completed`
+    /// count says which write failed: zero if the output never left its previous
+    /// code, or one if the rest write failed and the output may be left at
+    /// `active_code`.
+    pub fn pulse<D: DelayNs>(
+        &mut self,
+        active_code: u16,
+        rest_code: u16,
+        width_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        self.pace(delay);
+        self.write_update_dac(active_code)
+            .map_err(|error| Interrupted {
+                completed: 0,
+                error,
+            })?;
+        delay.delay_us(width_us);
+        self.pace(delay);
+        self.write_update_dac(rest_code)
+            .map_err(|error| Interrupted {
+                completed: 1,
+                error,
+            })
+    }
+
+    /// Play a buffer of codes out of the DAC at a fixed sample period.
+    ///
+    /// Each sample is written with `write_update_dac()` and then held for
+    /// `sample_period_us`. The delay doesn't account for the time the write takes,
+    /// so the real period is slightly longer.
+    ///
+    /// With a rate limit set, each sample first waits for it with `pace()`.
+    ///
+    /// Stops at the first error, reporting how many samples were emitted before it.
+    pub fn play_samples<D: DelayNs>(
+        &mut self,
+        samples: &[u16],
+        sample_period_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        for (completed, &sample) in samples.iter().enumerate() {
+            self.pace(delay);
+            self.write_update_dac(sample)
+                .map_err(|error| Interrupted { completed, error })?;
+            delay.delay_us(sample_period_us);
+        }
+
+        Ok(())
+    }
+
+    /// Step the output from `start` to `end` in increments of `step`.
+    ///
+    /// Each code is written with `write_update_dac()` and held for `dwell_us`,
+    /// then passed to `on_step`, so the callback runs once the output has
+    /// settled. Sweeps run up or down, and `end` is always the last code even
+    /// when the range isn't a multiple of `step`. A `step` of zero is treated as
+    /// one. Codes above `max_code()` are handled by the overflow policy before
+    /// anything is written.
+    pub fn sweep<D: DelayNs>(
+        &mut self,
+        start: u16,
+        end: u16,
+        step: u16,
+        dwell_us: u32,
+        delay: &mut D,
+        mut on_step: impl FnMut(u16),
+    ) -> Result<(), Error<I2C::Error>> {
+        let start = self.limit_code(start as i64)?;
+        let end = self.limit_code(end as i64)?;
+        let step = step.max(1);
+
+        let mut code = start;
+        loop {
+            self.pace(delay);
+            self.write_update_dac(code)?;
+            delay.delay_us(dwell_us);
+            on_step(code);
+
+            if code == end {
+                return Ok(());
+            }
+            code = if end > code {
+                code.saturating_add(step).min(end)
+            } else {
+                code.saturating_sub(step).max(end)
+            };
+        }
+    }
+
+    /// Write each code and measure the output, to capture the transfer function.
+    ///
+    /// Each code is written with `write_update_dac()` and held for `dwell_us`,
+    /// then `measure` is called to read the output in microvolts, and the
+    /// reading is stored at the same index of `out`. Returns the number of
+    /// readings stored, which is all of them on success. The code and reading
+    /// pairs suit `set_two_point_calibration()`.
+    ///
+    /// Stops at the first error, reporting how many readings were stored before
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` isn't the same length as `codes`.
+    pub fn characterize<F: FnMut() -> u32, D: DelayNs>(
+        &mut self,
+        codes: &[u16],
+        dwell_us: u32,
+        mut measure: F,
+        out: &mut [u32],
+        delay: &mut D,
+    ) -> Result<usize, Interrupted<I2C::Error>> {
+        assert_eq!(out.len(), codes.len(), "one reading is stored per code");
+
+        for (completed, (&code, reading)) in codes.iter().zip(out.iter_mut()).enumerate() {
+            self.pace(delay);
+            self.write_update_dac(code)
+                .map_err(|error| Interrupted { completed, error })?;
+            delay.delay_us(dwell_us);
+            *reading = measure();
+        }
+
+        Ok(codes.len())
+    }
+
+    /// Play codes from an iterator out of the DAC at a fixed sample period.
+    ///
+    /// Like `play_samples()`, but the samples can be generated as they are played,
+    /// so waveforms of any length don't need a buffer. Samples above `max_code()`
+    /// clamp to it. Playback ends when the iterator does.
+    ///
+    /// Stops at the first error, reporting how many samples were emitted before it.
+    pub fn play_iter<I: Iterator<Item = u16>, D: DelayNs>(
+        &mut self,
+        samples: I,
+        sample_period_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        for (completed, sample) in samples.enumerate() {
+            self.pace(delay);
+            self.write_update_dac(sample.min(self.max_code()))
+                .map_err(|error| Interrupted { completed, error })?;
+            delay.delay_us(sample_period_us);
+        }
+
+        Ok(())
+    }
+
+    /// Fade the output from the current code to `target` along an easing curve.
+    ///
+    /// The fade writes one code per millisecond with `write_update_dac()`, so it
+    /// takes `duration_ms` steps, each written after a 1ms wait. The last step is
+    /// always exactly `target`, and a `duration_ms` of zero writes `target` at
+    /// once. A `target` above `max_code()` is handled by the overflow policy.
+    ///
+    /// Returns `Error::UnknownState` if no code has been written since the driver
+    /// was created, as there is nothing to fade from.
+    pub fn fade_to<D: DelayNs>(
+        &mut self,
+        target: u16,
+        duration_ms: u32,
+        curve: EasingCurve,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.sync_if_lazy();
+        let start = self.dac_code.ok_or(Error::UnknownState)?;
+        let target = self.limit_code(target as i64)?;
+        if duration_ms == 0 {
+            self.pace(delay);
+            return self.write_update_dac(target);
+        }
+
+        let span = target as i64 - start as i64;
+        for step in 1..=duration_ms {
+            let code = if step == duration_ms {
+                target
+            } else {
+                let progress = ease(curve, step, duration_ms) as i64;
+                let offset = (span * progress + EASE_ONE as i64 / 2).div_euclid(EASE_ONE as i64);
+                (start as i64 + offset) as u16
+            };
+
+            delay.delay_ms(1);
+            self.pace(delay);
+            self.write_update_dac(code)?;
+        }
+
+        Ok(())
+    }
+
+    /// Approximate a code between two DAC codes by alternating between them.
+    ///
+    /// `target_sub_lsb` is a code with 8 fraction bits, so `0x0180` is code 1.5.
+    /// Over `frame_count` frames of `frame_period_us`, the output spends the
+    /// fraction of frames closest to the target's fraction at the code above it,
+    /// spread evenly, and the rest at the code below. The time-averaged code is
+    /// then within `1 / (2 * frame_count)` of the target. Only frames that change
+    /// the code make a bus write.
+    ///
+    /// A target above `max_code()` is handled by the overflow policy.
+    pub fn write_dithered<D: DelayNs>(
+        &mut self,
+        target_sub_lsb: u32,
+        frame_count: u16,
+        frame_period_us: u32,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        let one = 1 << DITHER_FRACTION_BITS;
+        let max_target = (self.max_code() as u32) << DITHER_FRACTION_BITS;
+        let target = if target_sub_lsb > max_target {
+            (self.limit_code(self.max_code() as i64 + 1)? as u32) << DITHER_FRACTION_BITS
+        } else {
+            target_sub_lsb
+        };
+
+        let floor = (target >> DITHER_FRACTION_BITS) as u16;
+        let fraction = target & (one - 1);
+        let frames = frame_count as u32;
+        let ceil_frames = (fraction * frames + one / 2) / one;
+
+        for frame in 0..frames {
+            // Frames where the running count of ceil frames steps up
+            let is_ceil = (frame + 1) * ceil_frames / frames > frame * ceil_frames / frames;
+            self.pace(delay);
+            self.write_update_if_changed(floor + is_ceil as u16)?;
+            delay.delay_us(frame_period_us);
+        }
+
+        Ok(())
+    }
+}
+
+/// Fraction bits in the sub-LSB targets of `write_dithered()`.
+const DITHER_FRACTION_BITS: u32 = 8;
+
+/// Progress of 1.0 in the Q16 fixed point used by `ease()`.
+const EASE_ONE: u64 = 1 << 16;
+
+/// Progress along `curve` at `step` of `steps`, from zero to `EASE_ONE`.
+fn ease(curve: EasingCurve, step: u32, steps: u32) -> u64 {
+    let t = step as u64 * EASE_ONE / steps as u64;
+
+    match curve {
+        EasingCurve::Linear => t,
+        EasingCurve::Gamma(power) => (1..power).fold(t, |progress, _| progress * t / EASE_ONE),
+        EasingCurve::EaseInOut => {
+            let t2 = t * t / EASE_ONE;
+            let t3 = t2 * t / EASE_ONE;
+            3 * t2 - 2 * t3
+        }
+    }
+}
+
+/// Code at `step` of a linear ramp from `start` to `target` over `steps` steps.
+///
+/// Step `steps` is exactly `target`.
+pub(crate) fn ramp_code(start: u16, target: u16, step: u16, steps: u16) -> u16 {
+    let span = target as i64 - start as i64;
+
+    (start as i64 + span * step as i64 / steps as i64) as u16
+}
@@ -0,0 +1,64 @@
+//! Voltages tagged with their unit, for the voltage writes.
+//!
+//! Conversions between the units round to the nearest value, with halfway
+//! values rounded up, and saturate at the limits of the target type. Negative
+//! and NaN `Volts` convert to zero.
+
+/// A voltage in millivolts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Millivolts(pub u32);
+
+/// A voltage in microvolts
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Microvolts(pub u64);
+
+/// A voltage in volts
+#[cfg(feature = "float")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Volts(pub f32);
+
+impl From<Millivolts> for Microvolts {
+    fn from(mv: Millivolts) -> Self {
+        Microvolts(mv.0 as u64 * 1000)
+    }
+}
+
+impl From<Microvolts> for Millivolts {
+    fn from(uv: Microvolts) -> Self {
+        let mv = uv.0 / 1000 + (uv.0 % 1000 >= 500) as u64;
+
+        Millivolts(mv.min(u32::MAX as u64) as u32)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Millivolts> for Volts {
+    fn from(mv: Millivolts) -> Self {
+        Volts(mv.0 as f32 / 1000.0)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Microvolts> for Volts {
+    fn from(uv: Microvolts) -> Self {
+        Volts(uv.0 as f32 / 1_000_000.0)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Volts> for Millivolts {
+    fn from(volts: Volts) -> Self {
+        // Float to int casts saturate, and NaN becomes zero
+        Millivolts((volts.0 * 1000.0 + 0.5) as u32)
+    }
+}
+
+#[cfg(feature = "float")]
+impl From<Volts> for Microvolts {
+    fn from(volts: Volts) -> Self {
+        Microvolts((volts.0 as f64 * 1_000_000.0 + 0.5) as u64)
+    }
+}
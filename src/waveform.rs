@@ -0,0 +1,314 @@
+//! Lookup tables of one waveform period, for use with `play_samples()`.
+//!
+//! Each table is `N` samples of `offset_code + amplitude_code * f(phase)`, where
+//! `f` spans -1 to 1, rounded to the nearest code and clamped to the codes the
+//! resolution accepts. The tables use integer math only and are `const fn`, so
+//! they can be built at compile time.
+//!
+//! `samples_period_us()` and `WaveformPlayer` turn a table and a frequency into
+//! the sample period to play it at. `Chirp` generates a sine wave swept
+//! between two frequencies as it is played, without a table.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{AdafruitAD569x, Interrupted, Resolution, ShutdownControl};
+
+/// Fixed-point one, as phase-shaped values are Q30.
+const ONE: i64 = 1 << 30;
+
+/// Bits of fraction in a phase, measured in turns.
+const PHASE_BITS: u32 = 24;
+
+/// A quarter turn of phase.
+const QUARTER: i64 = 1 << (PHASE_BITS - 2);
+
+/// 2π in Q30.
+const TWO_PI: i64 = 6_746_518_852;
+
+/// A sine wave starting at `offset_code` and rising.
+pub const fn sine_table<const N: usize>(
+    resolution: Resolution,
+    amplitude_code: u16,
+    offset_code: u16,
+) -> [u16; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    while i < N {
+        let value = sine(fold(phase(i, N)));
+        table[i] = scale(resolution, amplitude_code, offset_code, value);
+        i += 1;
+    }
+
+    table
+}
+
+/// A triangle wave starting at `offset_code` and rising, peaking at a quarter
+/// period like `sine_table()`.
+pub const fn triangle_table<const N: usize>(
+    resolution: Resolution,
+    amplitude_code: u16,
+    offset_code: u16,
+) -> [u16; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    while i < N {
+        let value = fold(phase(i, N)) * (ONE / QUARTER);
+        table[i] = scale(resolution, amplitude_code, offset_code, value);
+        i += 1;
+    }
+
+    table
+}
+
+/// A sawtooth wave rising from `offset_code - amplitude_code` to just below
+/// `offset_code + amplitude_code`.
+pub const fn sawtooth_table<const N: usize>(
+    resolution: Resolution,
+    amplitude_code: u16,
+    offset_code: u16,
+) -> [u16; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    while i < N {
+        let value = (phase(i, N) - 2 * QUARTER) * (ONE / (2 * QUARTER));
+        table[i] = scale(resolution, amplitude_code, offset_code, value);
+        i += 1;
+    }
+
+    table
+}
+
+/// Sample period in microseconds that plays `samples_per_period` samples per
+/// waveform period at `frequency_hz`.
+///
+/// The period is `1_000_000 / (frequency_hz * samples_per_period)`, rounded to
+/// the nearest microsecond with halfway values rounded up. A zero frequency or
+/// sample count is treated as one. The lowest frequency is 1Hz, a period of
+/// `1_000_000 / samples_per_period`. Above `1_000_000 / samples_per_period`Hz
+/// the period is under a microsecond and rounds to one or zero, so the
+/// achievable frequency is then set by the bus, not the table. The time each
+/// write takes is not subtracted, so the real period is slightly longer.
+pub const fn samples_period_us(frequency_hz: u32, samples_per_period: u16) -> u32 {
+    let frequency = if frequency_hz == 0 {
+        1
+    } else {
+        frequency_hz as u64
+    };
+    let samples = if samples_per_period == 0 {
+        1
+    } else {
+        samples_per_period as u64
+    };
+    let rate = frequency * samples;
+
+    ((1_000_000 + rate / 2) / rate) as u32
+}
+
+/// A waveform table coupled with the frequency to play it at
+///
+/// The sample period is worked out once with `samples_period_us()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WaveformPlayer<'a> {
+    table: &'a [u16],
+    sample_period_us: u32,
+}
+
+impl<'a> WaveformPlayer<'a> {
+    /// Play `table`, one waveform period long, at `frequency_hz`.
+    ///
+    /// Tables longer than `u16::MAX` samples have their period worked out as if
+    /// they were `u16::MAX` long.
+    pub fn new(table: &'a [u16], frequency_hz: u32) -> Self {
+        let samples = table.len().min(u16::MAX as usize) as u16;
+
+        Self {
+            table,
+            sample_period_us: samples_period_us(frequency_hz, samples),
+        }
+    }
+
+    /// The table being played.
+    pub fn table(&self) -> &'a [u16] {
+        self.table
+    }
+
+    /// Time each sample is held for, in microseconds.
+    pub fn sample_period_us(&self) -> u32 {
+        self.sample_period_us
+    }
+
+    /// Play `periods` whole periods of the waveform with `play_iter()`.
+    ///
+    /// Stops at the first error, reporting how many samples were emitted before
+    /// it, counted across all the periods.
+    pub fn play<I2C: I2c, LDAC, RST, SHDN: ShutdownControl, D: DelayNs>(
+        &self,
+        dac: &mut AdafruitAD569x<I2C, LDAC, RST, SHDN>,
+        periods: u32,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        let samples = (0..periods).flat_map(|_| self.table.iter().copied());
+
+        dac.play_iter(samples, self.sample_period_us, delay)
+    }
+}
+
+/// A linear frequency sweep of a sine wave, generated sample by sample
+///
+/// The frequency moves linearly from `start_hz` to `end_hz` over the sweep. Each
+/// sample advances a phase accumulator by an increment worked out for the
+/// frequency at that sample, so the phase stays continuous as the frequency
+/// changes. Samples are `offset_code + amplitude_code * sin(phase)`, clamped to
+/// the codes the resolution accepts, starting at `offset_code` and rising.
+///
+/// Frequencies are limited to half the sample rate, and higher ones are clamped
+/// to it. The sample rate is in turn limited by the bus: each sample is a
+/// four-byte write, taking about 100µs at 400kHz, so rates above about 10kHz
+/// aren't reached, and every frequency in the sweep comes out lower in
+/// proportion. The time each write takes is not subtracted from the sample
+/// period either.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Chirp {
+    resolution: Resolution,
+    amplitude_code: u16,
+    offset_code: u16,
+    start_increment: i64,
+    end_increment: i64,
+    samples: u32,
+    sample_period_us: u32,
+}
+
+impl Chirp {
+    /// Sweep from `start_hz` to `end_hz` over `duration_ms`, at `sample_rate_hz`
+    /// samples per second.
+    ///
+    /// The sweep is `duration_ms * sample_rate_hz / 1000` samples long, rounded
+    /// down. A zero sample rate is treated as 1Hz.
+    pub fn new(
+        resolution: Resolution,
+        start_hz: u32,
+        end_hz: u32,
+        duration_ms: u32,
+        sample_rate_hz: u32,
+        amplitude_code: u16,
+        offset_code: u16,
+    ) -> Self {
+        let sample_rate_hz = sample_rate_hz.max(1);
+        let samples = duration_ms as u64 * sample_rate_hz as u64 / 1000;
+
+        Self {
+            resolution,
+            amplitude_code,
+            offset_code,
+            start_increment: increment(start_hz, sample_rate_hz),
+            end_increment: increment(end_hz, sample_rate_hz),
+            samples: samples.min(u32::MAX as u64) as u32,
+            sample_period_us: samples_period_us(sample_rate_hz, 1),
+        }
+    }
+
+    /// Number of samples in the sweep.
+    pub fn len(&self) -> u32 {
+        self.samples
+    }
+
+    /// Whether the sweep has no samples, as when it is under one sample period long.
+    pub fn is_empty(&self) -> bool {
+        self.samples == 0
+    }
+
+    /// Time each sample is held for, in microseconds.
+    pub fn sample_period_us(&self) -> u32 {
+        self.sample_period_us
+    }
+
+    /// The codes of the sweep, in order.
+    pub fn samples(&self) -> impl Iterator<Item = u16> + use<> {
+        let chirp = *self;
+        let span = chirp.end_increment - chirp.start_increment;
+        let samples = chirp.samples.max(1) as i64;
+
+        (0..chirp.samples).scan(0u32, move |phase, n| {
+            let value = sine(fold((*phase >> (32 - PHASE_BITS)) as i64));
+            let increment = chirp.start_increment + span * n as i64 / samples;
+            *phase = phase.wrapping_add(increment as u32);
+
+            Some(scale(
+                chirp.resolution,
+                chirp.amplitude_code,
+                chirp.offset_code,
+                value,
+            ))
+        })
+    }
+
+    /// Play the sweep with `play_iter()`.
+    ///
+    /// Stops at the first error, reporting how many samples were emitted before it.
+    pub fn play<I2C: I2c, LDAC, RST, SHDN: ShutdownControl, D: DelayNs>(
+        &self,
+        dac: &mut AdafruitAD569x<I2C, LDAC, RST, SHDN>,
+        delay: &mut D,
+    ) -> Result<(), Interrupted<I2C::Error>> {
+        dac.play_iter(self.samples(), self.sample_period_us, delay)
+    }
+}
+
+/// Phase step per sample for `frequency_hz`, in 32-bit fractions of a turn,
+/// clamping the frequency to half the sample rate.
+fn increment(frequency_hz: u32, sample_rate_hz: u32) -> i64 {
+    let frequency = frequency_hz.min(sample_rate_hz / 2) as i64;
+
+    (frequency << 32) / sample_rate_hz as i64
+}
+
+/// Phase of sample `i` of `n`, as a fraction of a turn.
+const fn phase(i: usize, n: usize) -> i64 {
+    ((i as i64) << PHASE_BITS) / n as i64
+}
+
+/// Fold a phase into the quarter turn either side of zero, keeping its sine.
+const fn fold(phase: i64) -> i64 {
+    if phase < QUARTER {
+        phase
+    } else if phase < 3 * QUARTER {
+        2 * QUARTER - phase
+    } else {
+        phase - 4 * QUARTER
+    }
+}
+
+/// Sine of a folded phase, in Q30.
+///
+/// Uses the Taylor series to the 11th power, which is within about 1e-7 of the
+/// true value over a quarter turn either side of zero.
+const fn sine(phase: i64) -> i64 {
+    let x = (phase * TWO_PI) >> PHASE_BITS;
+    let x2 = (x * x) >> 30;
+
+    let mut term = ONE;
+    let mut k = 11;
+    while k > 1 {
+        term = ONE - ((x2 * term) >> 30) / (k * (k - 1));
+        k -= 2;
+    }
+
+    (x * term) >> 30
+}
+
+/// Scale a Q30 value in -1 to 1 into a clamped code.
+const fn scale(resolution: Resolution, amplitude_code: u16, offset_code: u16, value: i64) -> u16 {
+    let code = offset_code as i64 + ((amplitude_code as i64 * value + ONE / 2) >> 30);
+    let max_code = resolution.max_code() as i64;
+
+    if code < 0 {
+        0
+    } else if code > max_code {
+        max_code as u16
+    } else {
+        code as u16
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Error, Interrupted, OverflowPolicy, Resolution};
+use common::{ADDR, batch, write};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+#[test]
+fn verify_reads_back_only_the_last_code() {
+    let mut expectations = batch([write(1), write(2), write(3)]);
+    expectations.push(Transaction::write_read(ADDR, vec![0x30], vec![0x00, 0x03]));
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.verify_every_write(true);
+
+    dac.write_update_many(&[1, 2, 3]).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn verify_mismatch_counts_every_code_as_sent() {
+    let mut expectations = batch([write(1), write(2)]);
+    expectations.push(Transaction::write_read(ADDR, vec![0x30], vec![0x00, 0x04]));
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.verify_every_write(true);
+
+    assert_eq!(
+        dac.write_update_many(&[1, 2]),
+        Err(Interrupted {
+            completed: 2,
+            error: Error::VerifyMismatch {
+                expected: 2,
+                actual: 4
+            },
+        })
+    );
+
+    dac.release().done();
+}
+
+#[test]
+fn clamped_codes_are_sent_clamped() {
+    let expectations = batch([Transaction::write(ADDR, vec![0x30, 0xFF, 0xF0])]);
+    let mut dac =
+        AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, Resolution::Bits12);
+    dac.set_overflow_policy(OverflowPolicy::Clamp);
+
+    dac.write_update_many(&[0x2000]).unwrap();
+
+    assert_eq!(dac.current_code(), Some(0x0FFF));
+    dac.release().done();
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Calibration, Error, Millivolts};
+use common::{ADDR, write};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Mock;
+
+/// Nominal codes of 500mV, 1000mV and 2000mV on a 16-bit part at 2.5V.
+const FIT_CODES: [u16; 3] = [13107, 26214, 52429];
+
+/// Fit the calibration to the output `measure_uv` gives for each of `FIT_CODES`.
+fn fit(measure_uv: impl Fn(u16) -> u32) -> Result<Calibration, Error<ErrorKind>> {
+    let expectations: Vec<_> = FIT_CODES.into_iter().map(write).collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    let mut codes = FIT_CODES.into_iter();
+    let result = dac.calibrate_against(&[500, 1000, 2000], || measure_uv(codes.next().unwrap()));
+
+    dac.release().done();
+    result
+}
+
+/// Ideal output of a 16-bit code at 2.5V, in microvolts, rounded down.
+fn ideal_uv(code: u16) -> u32 {
+    (code as u64 * 2_500_000 / 65536) as u32
+}
+
+#[test]
+fn fit_of_an_ideal_output_is_no_correction() {
+    let calibration = fit(ideal_uv).unwrap();
+
+    assert_eq!(calibration.code_offset, 0);
+    assert_eq!(calibration.gain_numerator, calibration.gain_denominator);
+}
+
+#[test]
+fn fit_rounds_a_negative_offset_to_the_nearest_code() {
+    // 1mV high is 26.2 codes, corrected by taking 26 off
+    let calibration = fit(|code| ideal_uv(code) + 1000).unwrap();
+
+    assert_eq!(calibration.code_offset, -26);
+    assert_eq!(calibration.gain_numerator, calibration.gain_denominator);
+}
+
+#[test]
+fn fit_corrects_a_low_gain() {
+    // 38uV per code instead of 38.147, so about 0.39% more code is needed
+    let calibration = fit(|code| code as u32 * 38).unwrap();
+
+    assert_eq!(calibration.code_offset, 0);
+    assert_eq!(calibration.gain_numerator, 65789);
+    assert_eq!(calibration.gain_denominator, 65536);
+}
+
+#[test]
+fn fit_rejects_degenerate_measurements() {
+    assert_eq!(fit(|_| 1_000_000), Err(Error::InvalidConfig));
+    assert_eq!(
+        fit(|code| 3_000_000 - ideal_uv(code)),
+        Err(Error::InvalidConfig)
+    );
+}
+
+#[test]
+fn two_point_calibration_rejects_equal_codes() {
+    // The failed fit leaves 1000mV at its nominal code
+    let expectations = [write(26214)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    assert_eq!(
+        dac.set_two_point_calibration((1000, 40_000), (1000, 50_000)),
+        Err(Error::InvalidConfig)
+    );
+    dac.write_millivolts(Millivolts(1000)).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn two_point_calibration_matches_the_fit() {
+    let expectations = [write(26214 - 26)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.set_two_point_calibration(
+        (13107, ideal_uv(13107) + 1000),
+        (52429, ideal_uv(52429) + 1000),
+    )
+    .unwrap();
+    dac.write_millivolts(Millivolts(1000)).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn fit_past_the_calibration_range_is_refused() {
+    // 2V high is over 52000 codes, too many for the i16 offset
+    assert_eq!(
+        fit(|code| ideal_uv(code) + 2_000_000),
+        Err(Error::ValueOutOfRange)
+    );
+
+    let mut dac = AdafruitAD569x::new(Mock::new(&[]), ADDR);
+    assert_eq!(
+        dac.set_two_point_calibration((0, 2_000_000), (52429, ideal_uv(52429) + 2_000_000)),
+        Err(Error::ValueOutOfRange)
+    );
+    // A nearly flat output needs a gain past u32::MAX
+    assert_eq!(
+        dac.set_two_point_calibration((0, 1), (65535, 2)),
+        Err(Error::ValueOutOfRange)
+    );
+    assert_eq!(dac.calibration(), Calibration::default());
+
+    dac.release().done();
+}
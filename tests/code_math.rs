@@ -0,0 +1,22 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Resolution};
+use common::ADDR;
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn offset_code_saturates_at_both_ends() {
+    let dac = AdafruitAD569x::with_resolution(Mock::new(&[]), ADDR, Resolution::Bits12);
+
+    assert_eq!(dac.offset_code(100, -40), 60);
+    assert_eq!(dac.offset_code(100, -101), 0);
+    assert_eq!(dac.offset_code(0, i32::MIN), 0);
+    assert_eq!(dac.offset_code(4000, 95), 4095);
+    assert_eq!(dac.offset_code(4095, 1), 4095);
+    assert_eq!(dac.offset_code(u16::MAX, i32::MAX), 4095);
+
+    assert_eq!(dac.clamp_code(-1), 0);
+    assert_eq!(dac.clamp_code(4096), 4095);
+
+    dac.release().done();
+}
@@ -0,0 +1,21 @@
+//! Helpers shared by the integration tests
+#![allow(dead_code)]
+
+use embedded_hal_mock::eh1::i2c::Transaction;
+
+/// Address of the test part, with A0 low.
+pub const ADDR: u8 = 0x4C;
+
+/// The frame writing `code` to the input and DAC registers.
+pub fn write(code: u16) -> Transaction {
+    let [high, low] = code.to_be_bytes();
+    Transaction::write(ADDR, vec![0x30, high, low])
+}
+
+/// Expectations for one batch of `write_update_many()` holding `frames`.
+pub fn batch(frames: impl IntoIterator<Item = Transaction>) -> Vec<Transaction> {
+    let mut expectations = vec![Transaction::transaction_start(ADDR)];
+    expectations.extend(frames);
+    expectations.push(Transaction::transaction_end(ADDR));
+    expectations
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Error, OverflowPolicy};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn dither_alternates_codes_for_a_half_lsb_target() {
+    let expectations = [write(1), write(2), write(1), write(2)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_dithered(0x0180, 4, 10, &mut NoopDelay::new())
+        .unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn dither_of_whole_codes_writes_once() {
+    // A fraction within half a frame of a whole code rounds to it
+    let expectations = [write(3), write(2)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_dithered(0x0300, 4, 10, &mut NoopDelay::new())
+        .unwrap();
+    dac.write_dithered(0x01FF, 4, 10, &mut NoopDelay::new())
+        .unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn dither_above_full_scale_follows_the_overflow_policy() {
+    let expectations = [write(0xFFFF)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    assert_eq!(
+        dac.write_dithered(0x00FF_FF01, 4, 10, &mut NoopDelay::new()),
+        Err(Error::ValueOutOfRange)
+    );
+    dac.set_overflow_policy(OverflowPolicy::Clamp);
+    dac.write_dithered(0x00FF_FF01, 4, 10, &mut NoopDelay::new())
+        .unwrap();
+
+    dac.release().done();
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "float")]
+
+mod common;
+
+use ad569x::{AdafruitAD569x, DriftPoint, Volts};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::i2c::Mock;
+
+/// Points far enough apart that their temperature difference overflows `i16`.
+static WIDE: [DriftPoint; 2] = [
+    DriftPoint {
+        temperature_c: -30000,
+        gain_ppm: 0,
+        offset_code: 0,
+    },
+    DriftPoint {
+        temperature_c: 30000,
+        gain_ppm: 0,
+        offset_code: 600,
+    },
+];
+
+static SINGLE: [DriftPoint; 1] = [DriftPoint {
+    temperature_c: 25,
+    gain_ppm: 0,
+    offset_code: 7,
+}];
+
+#[test]
+fn drift_interpolates_between_wide_points() {
+    let expectations = [write(300), write(150), write(599)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_drift_table(&WIDE);
+
+    dac.write_voltage_at_temp(Volts(0.0), 0).unwrap();
+    dac.write_voltage_at_temp(Volts(0.0), -15000).unwrap();
+    dac.write_voltage_at_temp(Volts(0.0), 29900).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn drift_holds_end_points_outside_the_table() {
+    let expectations = [write(0), write(600)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_drift_table(&WIDE);
+
+    dac.write_voltage_at_temp(Volts(0.0), i16::MIN).unwrap();
+    dac.write_voltage_at_temp(Volts(0.0), i16::MAX).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn single_point_drift_table_applies_everywhere() {
+    let expectations = [write(7), write(7), write(7)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_drift_table(&SINGLE);
+
+    for temp_c in [-40, 25, 85] {
+        dac.write_voltage_at_temp(Volts(0.0), temp_c).unwrap();
+    }
+
+    dac.release().done();
+}
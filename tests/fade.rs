@@ -0,0 +1,65 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, EasingCurve};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn linear_fade_up_ends_on_target() {
+    let expectations: Vec<_> = [995, 996, 997, 998, 999, 1000]
+        .into_iter()
+        .map(write)
+        .collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_update_dac(995).unwrap();
+    dac.fade_to(1000, 5, EasingCurve::Linear, &mut NoopDelay::new())
+        .unwrap();
+    assert_eq!(dac.current_code(), Some(1000));
+
+    dac.release().done();
+}
+
+#[test]
+fn linear_fade_down_ends_on_target() {
+    let expectations: Vec<_> = [1000, 999, 998, 997, 996, 995]
+        .into_iter()
+        .map(write)
+        .collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_update_dac(1000).unwrap();
+    dac.fade_to(995, 5, EasingCurve::Linear, &mut NoopDelay::new())
+        .unwrap();
+    assert_eq!(dac.current_code(), Some(995));
+
+    dac.release().done();
+}
+
+#[test]
+fn long_fade_down_rounds_to_nearest_and_ends_on_target() {
+    let expectations: Vec<_> = [995, 665, 335, 5].into_iter().map(write).collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_update_dac(995).unwrap();
+    dac.fade_to(5, 3, EasingCurve::Linear, &mut NoopDelay::new())
+        .unwrap();
+    assert_eq!(dac.current_code(), Some(5));
+
+    dac.release().done();
+}
+
+#[test]
+fn eased_fades_end_on_target() {
+    for (curve, halfway) in [(EasingCurve::Gamma(3), 999), (EasingCurve::EaseInOut, 998)] {
+        let expectations = [write(1000), write(halfway), write(995)];
+        let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+        dac.write_update_dac(1000).unwrap();
+        dac.fade_to(995, 2, curve, &mut NoopDelay::new()).unwrap();
+        assert_eq!(dac.current_code(), Some(995));
+
+        dac.release().done();
+    }
+}
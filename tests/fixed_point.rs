@@ -0,0 +1,77 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Error, OverflowPolicy};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::i2c::Mock;
+
+/// One code step of a 16-bit part at 2.5V, in Q16.16 millivolts.
+const Q16_LSB: i32 = 2500;
+
+#[test]
+fn q16_16_millivolts_round_to_the_nearest_code() {
+    let expectations = [write(0x8000), write(0x000B), write(0x000A), write(0xFFFF)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_q16_16_millivolts(1250 << 16).unwrap();
+    dac.write_q16_16_millivolts(10 * Q16_LSB + Q16_LSB / 2)
+        .unwrap();
+    dac.write_q16_16_millivolts(10 * Q16_LSB + Q16_LSB / 2 - 1)
+        .unwrap();
+    dac.write_q16_16_millivolts(65535 * Q16_LSB).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn negative_q16_16_millivolts_round_down() {
+    let expectations = [write(0x0000), write(0x0000)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    // Half a code below zero rounds up to zero, anything lower is code -1
+    dac.write_q16_16_millivolts(-Q16_LSB / 2).unwrap();
+    assert_eq!(
+        dac.write_q16_16_millivolts(-Q16_LSB / 2 - 1),
+        Err(Error::ValueOutOfRange)
+    );
+    dac.set_overflow_policy(OverflowPolicy::Clamp);
+    dac.write_q16_16_millivolts(i32::MIN).unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn q16_16_millivolts_past_full_scale_are_out_of_range() {
+    let mut dac = AdafruitAD569x::new(Mock::new(&[]), ADDR);
+
+    assert_eq!(
+        dac.write_q16_16_millivolts(65535 * Q16_LSB + Q16_LSB / 2),
+        Err(Error::ValueOutOfRange)
+    );
+    assert_eq!(
+        dac.write_q16_16_millivolts(i32::MAX),
+        Err(Error::ValueOutOfRange)
+    );
+
+    let mut dac = dac.with_vref(0);
+    assert_eq!(
+        dac.write_q16_16_millivolts(1 << 16),
+        Err(Error::InvalidConfig)
+    );
+
+    dac.release().done();
+}
+
+#[test]
+fn codes_convert_to_q16_16_millivolts() {
+    let dac = AdafruitAD569x::new(Mock::new(&[]), ADDR);
+    assert_eq!(dac.code_to_q16_16_millivolts(0), 0);
+    assert_eq!(dac.code_to_q16_16_millivolts(10), 10 * Q16_LSB);
+    assert_eq!(dac.code_to_q16_16_millivolts(0x8000), 1250 << 16);
+    assert_eq!(dac.code_to_q16_16_millivolts(0xFFFF), 65535 * Q16_LSB);
+    dac.release().done();
+
+    // Just under 40V is past the largest Q16.16 value
+    let dac = AdafruitAD569x::new(Mock::new(&[]), ADDR).with_vref(40_000);
+    assert_eq!(dac.code_to_q16_16_millivolts(0xFFFF), i32::MAX);
+    dac.release().done();
+}
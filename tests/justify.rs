@@ -0,0 +1,82 @@
+mod common;
+
+use core::convert::Infallible;
+
+use ad569x::{AdafruitAD569x, Resolution};
+use common::ADDR;
+use embedded_hal::i2c::{ErrorType, I2c, Operation};
+
+/// Bus that stores written register words and echoes them back on reads.
+#[derive(Default)]
+struct EchoBus {
+    input: u16,
+    dac: u16,
+    pointer: u8,
+}
+
+impl ErrorType for EchoBus {
+    type Error = Infallible;
+}
+
+impl I2c for EchoBus {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        assert_eq!(address, ADDR);
+
+        for operation in operations {
+            match operation {
+                Operation::Write([pointer]) => self.pointer = *pointer,
+                Operation::Write([command, high, low]) => {
+                    let word = u16::from_be_bytes([*high, *low]);
+                    match command {
+                        0x10 => self.input = word,
+                        0x30 => (self.input, self.dac) = (word, word),
+                        _ => {}
+                    }
+                }
+                Operation::Write(bytes) => panic!("unexpected write {bytes:02x?}"),
+                Operation::Read(buffer) => {
+                    let word = match self.pointer {
+                        0x10 => self.input,
+                        0x30 => self.dac,
+                        pointer => panic!("unexpected read pointer {pointer:#04x}"),
+                    };
+                    buffer.copy_from_slice(&word.to_be_bytes());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn twelve_bit_round_trip_is_lossless() {
+    let mut dac = AdafruitAD569x::with_resolution(EchoBus::default(), ADDR, Resolution::Bits12);
+
+    for bit in 0..12 {
+        let code = 1 << bit;
+
+        dac.write_update_dac(code).unwrap();
+        assert_eq!(dac.read_dac().unwrap(), code);
+
+        dac.write_dac(code).unwrap();
+        assert_eq!(dac.read_input().unwrap(), code);
+    }
+
+    let bus = dac.release();
+    assert_eq!(bus.dac, 1 << 15);
+    assert_eq!(bus.input, 1 << 15);
+}
+
+#[test]
+fn twelve_bit_code_one_lands_at_bit_four() {
+    let mut dac = AdafruitAD569x::with_resolution(EchoBus::default(), ADDR, Resolution::Bits12);
+
+    dac.write_update_dac(1).unwrap();
+
+    assert_eq!(dac.release().dac, 1 << 4);
+}
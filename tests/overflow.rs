@@ -0,0 +1,28 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Error, OverflowPolicy, Resolution};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn clamp_policy_writes_max_code() {
+    let expectations = [write(0xFFF0)];
+    let mut dac =
+        AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, Resolution::Bits12);
+    dac.set_overflow_policy(OverflowPolicy::Clamp);
+
+    assert_eq!(dac.write_update_dac(0x1000), Ok(()));
+
+    dac.release().done();
+}
+
+#[test]
+fn error_policy_rejects_without_writing() {
+    let mut dac = AdafruitAD569x::with_resolution(Mock::new(&[]), ADDR, Resolution::Bits12);
+    dac.set_overflow_policy(OverflowPolicy::Error);
+
+    assert_eq!(dac.write_update_dac(0x1000), Err(Error::ValueOutOfRange));
+    assert_eq!(dac.write_dac(0x1000), Err(Error::ValueOutOfRange));
+
+    dac.release().done();
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use std::cell::Cell;
+
+use ad569x::{AdafruitAD569x, Error, Gain, RateLimit, RateLimitPolicy};
+use common::{ADDR, batch, write};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+thread_local! {
+    static NOW_US: Cell<u32> = const { Cell::new(0) };
+}
+
+fn now_us() -> u32 {
+    NOW_US.with(Cell::get)
+}
+
+fn set_now_us(now: u32) {
+    NOW_US.with(|cell| cell.set(now));
+}
+
+/// `DelayUs` that advances the mock clock instead of sleeping.
+fn advance_us(us: u32) {
+    set_now_us(now_us().wrapping_add(us));
+}
+
+/// Delay that advances the mock clock instead of sleeping.
+struct ClockDelay;
+
+impl DelayNs for ClockDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        set_now_us(now_us().wrapping_add(ns.div_ceil(1000)));
+    }
+}
+
+#[test]
+fn refuse_rejects_early_writes_without_touching_the_bus() {
+    set_now_us(1000);
+    let expectations = [write(1), write(3)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(
+        RateLimit::new(100, now_us).with_policy(RateLimitPolicy::Refuse),
+    ));
+
+    dac.write_update_dac(1).unwrap();
+    set_now_us(1040);
+    assert_eq!(dac.write_wait_us(), 60);
+    assert_eq!(
+        dac.write_update_dac(2),
+        Err(Error::RateLimited { wait_us: 60 })
+    );
+    set_now_us(1100);
+    dac.write_update_dac(3).unwrap();
+
+    assert_eq!(dac.current_code(), Some(3));
+    assert_eq!(dac.stats().rate_limited, 1);
+    dac.release().done();
+}
+
+#[test]
+fn observe_writes_anyway_and_counts() {
+    set_now_us(u32::MAX - 10);
+    let expectations = [write(1), write(2), write(3)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(
+        RateLimit::new(100, now_us).with_policy(RateLimitPolicy::Observe),
+    ));
+
+    dac.write_update_dac(1).unwrap();
+    // The clock wraps between writes
+    set_now_us(20);
+    dac.write_update_dac(2).unwrap();
+    set_now_us(200);
+    dac.write_update_dac(3).unwrap();
+
+    assert_eq!(dac.stats().rate_limited, 1);
+    dac.release().done();
+}
+
+#[test]
+fn pace_waits_out_the_interval_with_the_delay() {
+    set_now_us(0);
+    let expectations = [write(1), write(2), write(3)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(RateLimit::new(100, now_us)));
+
+    dac.play_samples(&[1, 2, 3], 10, &mut ClockDelay).unwrap();
+
+    // Each sample after the first waits 90us on top of the 10us period
+    assert_eq!(now_us(), 210);
+    assert_eq!(dac.stats().rate_limited, 0);
+    dac.release().done();
+}
+
+#[test]
+fn begin_goes_ahead_under_the_default_policy() {
+    set_now_us(0);
+    let expectations = [
+        Transaction::write(ADDR, vec![0x40, 0x80, 0x00]),
+        Transaction::write(ADDR, vec![0x40, 0x00, 0x00]),
+    ];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(RateLimit::new(100, now_us)));
+
+    dac.begin().unwrap();
+
+    assert_eq!(dac.stats().rate_limited, 1);
+    dac.release().done();
+}
+
+#[test]
+fn delay_holds_back_each_write_of_begin() {
+    set_now_us(0);
+    let expectations = [
+        Transaction::write(ADDR, vec![0x40, 0x80, 0x00]),
+        Transaction::write(ADDR, vec![0x40, 0x00, 0x00]),
+    ];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(
+        RateLimit::new(100, now_us)
+            .with_policy(RateLimitPolicy::Refuse)
+            .with_delay(advance_us),
+    ));
+
+    dac.begin().unwrap();
+
+    assert_eq!(now_us(), 100);
+    dac.release().done();
+}
+
+#[test]
+fn delay_holds_back_each_write_of_a_gain_change() {
+    set_now_us(0);
+    let expectations = [
+        write(1000),
+        write(500),
+        Transaction::write(ADDR, vec![0x40, 0x08, 0x00]),
+    ];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(
+        RateLimit::new(100, now_us)
+            .with_policy(RateLimitPolicy::Refuse)
+            .with_delay(advance_us),
+    ));
+
+    dac.write_update_dac(1000).unwrap();
+    dac.set_gain_preserving_voltage(Gain::X2).unwrap();
+
+    assert_eq!(now_us(), 200);
+    assert_eq!(dac.stats().rate_limited, 2);
+    dac.release().done();
+}
+
+#[test]
+fn delay_holds_back_each_batch_of_write_update_many() {
+    set_now_us(0);
+    let codes: Vec<u16> = (1..=9).collect();
+    let expectations: Vec<_> = codes
+        .chunks(8)
+        .flat_map(|chunk| batch(chunk.iter().copied().map(write)))
+        .collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+    dac.set_rate_limit(Some(RateLimit::new(100, now_us).with_delay(advance_us)));
+
+    dac.write_update_many(&codes).unwrap();
+
+    assert_eq!(now_us(), 100);
+    assert_eq!(dac.current_code(), Some(9));
+    dac.release().done();
+}
+
+#[test]
+fn delay_holds_back_a_retry() {
+    set_now_us(0);
+    let expectations = [
+        write(1).with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data)),
+        write(1),
+    ];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR).with_retries(1);
+    dac.set_rate_limit(Some(RateLimit::new(100, now_us).with_delay(advance_us)));
+
+    dac.write_update_dac(1).unwrap();
+
+    assert_eq!(now_us(), 100);
+    dac.release().done();
+}
+
+#[test]
+fn refused_retry_reports_the_bus_error() {
+    set_now_us(0);
+    let nak = ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data);
+    let expectations = [Transaction::write(ADDR, vec![0x40, 0x08, 0x00]).with_error(nak)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR).with_retries(1);
+    dac.set_rate_limit(Some(
+        RateLimit::new(100, now_us).with_policy(RateLimitPolicy::Refuse),
+    ));
+
+    assert_eq!(dac.set_gain(Gain::X2), Err(Error::I2c(nak)));
+
+    assert_eq!(dac.stats().rate_limited, 1);
+    dac.release().done();
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Error};
+use common::ADDR;
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+#[test]
+fn reset_accepts_nak() {
+    let expectations = [Transaction::write(ADDR, vec![0x40, 0x80, 0x00])
+        .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    assert_eq!(dac.reset(), Ok(()));
+
+    dac.release().done();
+}
+
+#[test]
+fn reset_returns_other_bus_errors() {
+    let expectations =
+        [Transaction::write(ADDR, vec![0x40, 0x80, 0x00]).with_error(ErrorKind::ArbitrationLoss)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    assert_eq!(dac.reset(), Err(Error::I2c(ErrorKind::ArbitrationLoss)));
+
+    dac.release().done();
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Resolution};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn scale_anchors_on_14_bit_part() {
+    let expectations = [write(0x0000), write(0x8000), write(0xFFFC)];
+    let mut dac =
+        AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, Resolution::Bits14);
+
+    dac.set_zero_scale().unwrap();
+    dac.set_mid_scale().unwrap();
+    dac.set_full_scale().unwrap();
+
+    dac.release().done();
+}
+
+#[test]
+fn half_centipercent_is_mid_scale() {
+    for resolution in [Resolution::Bits12, Resolution::Bits14, Resolution::Bits16] {
+        let expectations = [write(0x8000)];
+        let mut dac = AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+        dac.write_centipercent(5000).unwrap();
+        assert_eq!(dac.current_code(), Some(dac.max_code() / 2 + 1));
+
+        dac.release().done();
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Config, DisabledOutputPolicy, Error, OperatingMode, UpdateKind};
+use common::ADDR;
+use embedded_hal_mock::eh1::digital::{self, State};
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+fn write_input(code: u16) -> Transaction {
+    let [high, low] = code.to_be_bytes();
+    Transaction::write(ADDR, vec![0x10, high, low])
+}
+
+fn reset() -> Transaction {
+    control(0x8000)
+}
+
+fn control(word: u16) -> Transaction {
+    let [high, low] = word.to_be_bytes();
+    Transaction::write(ADDR, vec![0x40, high, low])
+}
+
+fn update() -> Transaction {
+    Transaction::write(ADDR, vec![0x20, 0x00, 0x00])
+}
+
+#[test]
+fn update_after_a_write_is_fresh_and_a_repeat_is_stale() {
+    let expectations = [write_input(5), update(), update()];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.write_dac(5).unwrap();
+    assert_eq!(dac.update_dac(), Ok(UpdateKind::Fresh));
+    // Still made, but reported
+    assert_eq!(dac.update_dac(), Ok(UpdateKind::Stale));
+    assert_eq!(dac.current_code(), Some(5));
+
+    dac.release().done();
+}
+
+#[test]
+fn update_of_a_discarded_code_is_stale() {
+    let expectations = [write_input(7), update()];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    dac.prepare(7).unwrap().discard();
+    assert!(!dac.input_pending());
+    assert_eq!(dac.update_dac(), Ok(UpdateKind::Stale));
+
+    dac.release().done();
+}
+
+#[test]
+fn update_of_an_unknown_input_code_is_refused() {
+    let mut dac = AdafruitAD569x::new(Mock::new(&[]), ADDR);
+
+    assert_eq!(dac.update_dac(), Err(Error::UnknownState));
+
+    dac.release().done();
+}
+
+#[test]
+fn begin_gates_the_power_on_output() {
+    let config = Config::default().with_power_on_output_code(0x8000);
+    let expectations = [
+        reset(),
+        write_input(0x8000),
+        control(0x0000),
+        update(),
+        reset(),
+        write_input(0x8000),
+        control(0x0000),
+    ];
+    let mut shutdown_pin = digital::Mock::new(&[digital::Transaction::set(State::Low)]);
+    let mut dac = AdafruitAD569x::with_config(Mock::new(&expectations), ADDR, config)
+        .unwrap()
+        .with_shutdown_pin(shutdown_pin.clone());
+
+    dac.disable_output().unwrap();
+    dac.set_disabled_output_policy(DisabledOutputPolicy::Refuse);
+    // The code is preloaded, but not moved to the shut-down output
+    assert_eq!(dac.begin(), Err(Error::OutputDisabled));
+    assert_eq!(dac.current_code(), Some(0));
+    assert!(dac.input_pending());
+
+    dac.release().done();
+    shutdown_pin.done();
+}
+
+#[test]
+fn begin_refuses_a_power_on_output_while_powered_down() {
+    let config = Config::default()
+        .with_mode(OperatingMode::OutputTristate)
+        .with_power_on_output_code(0x8000);
+    let mut i2c = Mock::new(&[]);
+
+    let result = AdafruitAD569x::with_config(i2c.clone(), ADDR, config);
+    assert!(matches!(result, Err(Error::NotInOutputMode)));
+
+    i2c.done();
+}
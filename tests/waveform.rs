@@ -0,0 +1,67 @@
+mod common;
+
+use ad569x::waveform::Chirp;
+use ad569x::{AdafruitAD569x, Resolution};
+use common::{ADDR, write};
+use embedded_hal_mock::eh1::delay::NoopDelay;
+use embedded_hal_mock::eh1::i2c::Mock;
+
+#[test]
+fn chirp_at_a_fixed_frequency_is_a_sine() {
+    let chirp = Chirp::new(Resolution::Bits16, 1000, 1000, 4, 4000, 1000, 30000);
+
+    assert_eq!(chirp.len(), 16);
+    assert_eq!(chirp.sample_period_us(), 250);
+    let samples: Vec<_> = chirp.samples().collect();
+    assert_eq!(samples[..4], [30000, 31000, 30000, 29000]);
+    assert_eq!(samples[..4], samples[12..]);
+}
+
+#[test]
+fn chirp_sweeps_with_a_continuous_phase() {
+    // The first cycles are slow, so the output rises gradually from the offset
+    let chirp = Chirp::new(Resolution::Bits16, 10, 2000, 1, 8000, 1000, 30000);
+    let samples: Vec<_> = chirp.samples().collect();
+
+    assert_eq!(samples.len(), 8);
+    assert_eq!(samples[0], 30000);
+    assert!(samples[1] > 30000 && samples[1] < samples[2]);
+    assert!(samples.iter().all(|code| code.abs_diff(30000) <= 1000));
+}
+
+#[test]
+fn chirp_clamps_samples_and_frequencies() {
+    // Swings past both ends of a 12-bit range
+    let chirp = Chirp::new(Resolution::Bits12, 1000, 1000, 1, 4000, 3000, 2048);
+    let samples: Vec<_> = chirp.samples().collect();
+    assert_eq!(samples, [2048, 4095, 2048, 0]);
+
+    // Above half the sample rate is played at half the sample rate
+    let nyquist = Chirp::new(Resolution::Bits16, 2000, 2000, 1, 4000, 1000, 30000);
+    let above = Chirp::new(Resolution::Bits16, 3000, 9000, 1, 4000, 1000, 30000);
+    assert!(above.samples().eq(nyquist.samples()));
+}
+
+#[test]
+fn chirp_shorter_than_a_sample_is_empty() {
+    let chirp = Chirp::new(Resolution::Bits16, 100, 200, 0, 4000, 1000, 30000);
+    assert!(chirp.is_empty());
+    assert_eq!(chirp.samples().count(), 0);
+
+    // A zero sample rate is treated as 1Hz
+    let slow = Chirp::new(Resolution::Bits16, 100, 200, 999, 0, 1000, 30000);
+    assert!(slow.is_empty());
+    assert_eq!(slow.sample_period_us(), 1_000_000);
+}
+
+#[test]
+fn chirp_plays_each_sample() {
+    let expectations = [write(30000), write(31000), write(30000), write(29000)];
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    Chirp::new(Resolution::Bits16, 1000, 1000, 1, 4000, 1000, 30000)
+        .play(&mut dac, &mut NoopDelay::new())
+        .unwrap();
+
+    dac.release().done();
+}
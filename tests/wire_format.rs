@@ -0,0 +1,211 @@
+mod common;
+
+use ad569x::{AdafruitAD569x, Gain, OperatingMode, Resolution};
+use common::ADDR;
+use embedded_hal_mock::eh1::i2c::{Mock, Transaction};
+
+/// Run `f` on a 16-bit driver and check it writes exactly `frames`, in order.
+fn assert_writes<T>(frames: &[[u8; 3]], f: impl FnOnce(&mut AdafruitAD569x<Mock>) -> T) {
+    let expectations: Vec<_> = frames
+        .iter()
+        .map(|frame| Transaction::write(ADDR, frame.to_vec()))
+        .collect();
+    let mut dac = AdafruitAD569x::new(Mock::new(&expectations), ADDR);
+
+    f(&mut dac);
+
+    dac.release().done();
+}
+
+#[test]
+fn reset_writes_reset_bit() {
+    assert_writes(&[[0x40, 0x80, 0x00]], |dac| dac.reset().unwrap());
+}
+
+#[test]
+fn begin_resets_then_configures() {
+    assert_writes(&[[0x40, 0x80, 0x00], [0x40, 0x00, 0x00]], |dac| {
+        dac.begin().unwrap()
+    });
+}
+
+#[test]
+fn code_writes_are_big_endian() {
+    assert_writes(&[[0x10, 0x12, 0x34]], |dac| dac.write_dac(0x1234).unwrap());
+    assert_writes(&[[0x10, 0x12, 0x34], [0x20, 0x00, 0x00]], |dac| {
+        dac.write_dac(0x1234).unwrap();
+        dac.update_dac().unwrap();
+    });
+    assert_writes(&[[0x30, 0xAB, 0xCD]], |dac| {
+        dac.write_update_dac(0xABCD).unwrap()
+    });
+}
+
+#[test]
+fn scale_helpers() {
+    assert_writes(
+        &[[0x30, 0x00, 0x00], [0x30, 0x80, 0x00], [0x30, 0xFF, 0xFF]],
+        |dac| {
+            dac.set_zero_scale().unwrap();
+            dac.set_mid_scale().unwrap();
+            dac.set_full_scale().unwrap();
+        },
+    );
+}
+
+#[test]
+fn sub_16_bit_codes_are_left_justified() {
+    for (resolution, frame) in [
+        (Resolution::Bits12, [0x30, 0xFF, 0xF0]),
+        (Resolution::Bits14, [0x30, 0xFF, 0xFC]),
+        (Resolution::Bits16, [0x30, 0xFF, 0xFF]),
+    ] {
+        let expectations = [Transaction::write(ADDR, frame.to_vec())];
+        let mut dac = AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+        dac.write_update_dac(dac.max_code()).unwrap();
+
+        dac.release().done();
+    }
+}
+
+#[test]
+fn set_mode_packs_control_bits() {
+    let cases = [
+        (OperatingMode::NormalMode, true, Gain::X1, 0x00),
+        (OperatingMode::NormalMode, true, Gain::X2, 0x08),
+        (OperatingMode::NormalMode, false, Gain::X1, 0x10),
+        (OperatingMode::Output1kImpedance, true, Gain::X1, 0x20),
+        (OperatingMode::Output100kImpedance, true, Gain::X1, 0x40),
+        (OperatingMode::OutputTristate, false, Gain::X2, 0x78),
+    ];
+
+    for (mode, reference, gain, high_byte) in cases {
+        assert_writes(&[[0x40, high_byte, 0x00]], |dac| {
+            dac.set_mode(mode, reference, gain).unwrap()
+        });
+    }
+}
+
+#[test]
+fn control_helpers_keep_other_settings() {
+    assert_writes(
+        &[
+            [0x40, 0x08, 0x00],
+            [0x40, 0x18, 0x00],
+            [0x40, 0x78, 0x00],
+            [0x40, 0x18, 0x00],
+        ],
+        |dac| {
+            dac.set_gain(Gain::X2).unwrap();
+            dac.set_reference(false).unwrap();
+            dac.power_down(OperatingMode::OutputTristate).unwrap();
+            dac.wake().unwrap();
+        },
+    );
+}
+
+#[test]
+fn nop_writes_zero_payload() {
+    assert_writes(&[[0x00, 0x00, 0x00]], |dac| dac.nop().unwrap());
+}
+
+#[test]
+fn read_both_points_at_each_register_in_one_transaction() {
+    for (resolution, input, dac, codes) in [
+        (
+            Resolution::Bits12,
+            [0x12, 0x30],
+            [0xFF, 0xF0],
+            (0x0123, 0x0FFF),
+        ),
+        (
+            Resolution::Bits16,
+            [0x12, 0x34],
+            [0xFF, 0xFF],
+            (0x1234, 0xFFFF),
+        ),
+    ] {
+        let expectations = [
+            Transaction::transaction_start(ADDR),
+            Transaction::write(ADDR, vec![0x10]),
+            Transaction::read(ADDR, input.to_vec()),
+            Transaction::write(ADDR, vec![0x30]),
+            Transaction::read(ADDR, dac.to_vec()),
+            Transaction::transaction_end(ADDR),
+        ];
+        let mut driver =
+            AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+        assert_eq!(driver.read_both().unwrap(), codes);
+
+        driver.release().done();
+    }
+}
+
+#[test]
+fn every_command_and_resolution_has_the_documented_frame_layout() {
+    assert_eq!(ad569x::FRAME_LEN, 3);
+
+    for (resolution, shift) in [
+        (Resolution::Bits12, 4),
+        (Resolution::Bits14, 2),
+        (Resolution::Bits16, 0),
+    ] {
+        let max_code = resolution.max_code();
+        for code in [
+            0,
+            1,
+            0x00A5 & max_code,
+            max_code / 2 + 1,
+            max_code - 1,
+            max_code,
+        ] {
+            let [high, low] = (code << shift).to_be_bytes();
+            let expectations = [
+                Transaction::write(ADDR, vec![0x10, high, low]),
+                Transaction::write(ADDR, vec![0x20, 0x00, 0x00]),
+                Transaction::write(ADDR, vec![0x30, high, low]),
+                Transaction::write(ADDR, vec![0x00, 0x00, 0x00]),
+            ];
+            let mut dac =
+                AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+            dac.write_dac(code).unwrap();
+            dac.update_dac().unwrap();
+            dac.write_update_dac(code).unwrap();
+            dac.nop().unwrap();
+
+            dac.release().done();
+        }
+    }
+
+    // The spread-out bit patterns pin down the byte order and the shift
+    for (resolution, code, frame) in [
+        (Resolution::Bits12, 0x0ABC, [0x30, 0xAB, 0xC0]),
+        (Resolution::Bits14, 0x2ABC, [0x30, 0xAA, 0xF0]),
+        (Resolution::Bits16, 0xABCD, [0x30, 0xAB, 0xCD]),
+    ] {
+        let expectations = [Transaction::write(ADDR, frame.to_vec())];
+        let mut dac = AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+        dac.write_update_dac(code).unwrap();
+
+        dac.release().done();
+    }
+
+    // Control words aren't justified, whatever the resolution
+    for resolution in [Resolution::Bits12, Resolution::Bits14, Resolution::Bits16] {
+        let expectations = [
+            Transaction::write(ADDR, vec![0x40, 0x80, 0x00]),
+            Transaction::write(ADDR, vec![0x40, 0x38, 0x00]),
+        ];
+        let mut dac = AdafruitAD569x::with_resolution(Mock::new(&expectations), ADDR, resolution);
+
+        dac.reset().unwrap();
+        dac.set_mode(OperatingMode::Output1kImpedance, false, Gain::X2)
+            .unwrap();
+
+        dac.release().done();
+    }
+}